@@ -4,7 +4,17 @@ pub trait Advent {
 	/// The output desired for Part 2 of the puzzle.
 	type Answer2;
 
+	/// The knobs this puzzle lets you turn, and their default values.
+	///
+	/// These are the numbers the puzzle text makes up on the spot, like
+	/// which row to look at or how many rounds to play. They tend to be
+	/// different for the example and the real input, so the runner lets you
+	/// override them with `--param name=value`.
+	const PARAMS: &'static [(&'static str, i64)] = &[];
+
 	/// Convert input (from stdin, perhaps) into a usable data format.
+	///
+	/// Parameters should start out at their defaults from [`Advent::PARAMS`].
 	fn parse_input(input: &str) -> Self;
 	/// Solve the first part of the puzzle.
 	fn part_one(&self) -> Self::Answer1;
@@ -16,4 +26,82 @@ pub trait Advent {
 		self.part_one();
 		todo!("Part 2's puzzle is not yet known, give us a minute.");
 	}
+
+	/// Change one of the parameters listed in [`Advent::PARAMS`].
+	///
+	/// This only ever gets called with a name from that list, but the value
+	/// could be anything, so complain if it doesn't make sense.
+	fn set_param(&mut self, name: &str, value: i64) -> Result<(), String> {
+		Err(format!("Can't set {} to {}", name, value))
+	}
+}
+
+/// Convert a parameter's value into whatever type the puzzle actually uses.
+/// ```
+/// use advent::param_value;
+///
+/// assert_eq!(param_value::<u32>("rounds", 20), Ok(20));
+/// assert!(param_value::<u32>("rounds", -1).is_err());
+/// ```
+pub fn param_value<T>(name: &str, value: i64) -> Result<T, String>
+where
+	T: TryFrom<i64>,
+{
+	T::try_from(value)
+		.map_err(|_| format!("{} is out of range for {}", value, name))
+}
+
+/// Set a parameter by name, checking that the puzzle actually has it.
+/// ```
+/// use advent::{set_param, Advent};
+///
+/// struct Rows(i64);
+///
+/// impl Advent for Rows {
+///     type Answer1 = i64;
+///     type Answer2 = i64;
+///     const PARAMS: &'static [(&'static str, i64)] = &[("row", 10)];
+///
+///     fn parse_input(_: &str) -> Self {
+///         Rows(10)
+///     }
+///
+///     fn part_one(&self) -> i64 {
+///         self.0
+///     }
+///
+///     fn set_param(&mut self, _: &str, value: i64) -> Result<(), String> {
+///         self.0 = value;
+///         Ok(())
+///     }
+/// }
+///
+/// let mut rows = Rows::parse_input("");
+/// assert_eq!(set_param(&mut rows, "row", 20), Ok(()));
+/// assert_eq!(rows.part_one(), 20);
+/// assert!(set_param(&mut rows, "column", 20).is_err());
+/// ```
+pub fn set_param<T: Advent>(
+	puzzle: &mut T,
+	name: &str,
+	value: i64,
+) -> Result<(), String> {
+	check_param::<T>(name)?;
+	puzzle.set_param(name, value)
+}
+
+/// Make sure the puzzle has a parameter called `name`.
+pub(crate) fn check_param<T: Advent>(name: &str) -> Result<(), String> {
+	if T::PARAMS.iter().any(|&(param, _)| param == name) {
+		Ok(())
+	} else if T::PARAMS.is_empty() {
+		Err(String::from("This puzzle doesn't have any parameters"))
+	} else {
+		let names = T::PARAMS.iter().map(|&(n, _)| n).collect::<Vec<_>>();
+		Err(format!(
+			"No parameter called {}, try one of: {}",
+			name,
+			names.join(", ")
+		))
+	}
 }
@@ -0,0 +1,456 @@
+//! Day 1's Advent of Code puzzle
+//! =============================
+//! This used to live in `main.rs`, back before there were enough days to
+//! need a runner. Now it gets a file like everybody else.
+//!
+//! Puzzle input consists of a list of numbers, occasionally separated by
+//! empty lines. These represent a series of elves with varying amounts
+//! of snacks. An empty line separates one elf's collection from another.
+//! Non-empty lines represent the calorie count of the current snack.
+//! These elves are hungry.
+//!
+//! Part 1
+//! ------
+//! Return the elf with the best snacks. (Read: the elf with the highest
+//! calorie count)
+//!
+//! Part 2
+//! ------
+//! Return the calorie count of the top THREE elves, using the same criteria
+//! as part 1.
+//!
+//! Report
+//! ------
+//! Not part of the puzzle, but apparently somebody wants to know how these
+//! elves eat. `report` prints per-elf numbers and a histogram of totals
+//! instead of the answers, as a table or as CSV:
+//! `cargo run --bin day01 -- report csv 10000 < input.txt`. The last
+//! argument is how many calories wide each bar of the histogram is.
+
+use std::cmp::Reverse;
+use std::env;
+use std::fmt::Write;
+use std::ops::ControlFlow;
+use std::process;
+
+use advent::streaming::{parse_reader, StreamingAdvent};
+use advent::{input_to_str, Advent, Runner};
+
+/// How wide a histogram bucket is, unless told otherwise.
+const BUCKET_WIDTH: u32 = 5000;
+
+#[derive(Debug, PartialEq, Eq)]
+struct Day01 {
+	/// The snacks each elf is carrying, in input order.
+	elves: Vec<Vec<u32>>,
+}
+
+/// An elf's place in the snack rankings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct RankedElf {
+	/// Where this elf showed up in the input, starting from zero.
+	index: usize,
+	/// How many calories this elf is carrying.
+	total: u32,
+}
+
+/// What we need to remember while the inventory is coming in.
+#[derive(Debug, Default)]
+struct Inventory {
+	/// The snacks of every elf we've finished counting.
+	elves: Vec<Vec<u32>>,
+	/// The snacks of the elf we're in the middle of counting.
+	current: Vec<u32>,
+}
+
+/// Everything the report knows about one elf.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ElfStats {
+	/// Where this elf showed up in the input, starting from zero.
+	index: usize,
+	/// How many snacks this elf brought.
+	items: usize,
+	/// How many calories those add up to.
+	total: u32,
+	/// How many calories the average snack has.
+	mean: f64,
+	/// The biggest single snack.
+	largest: u32,
+}
+
+/// One bar of the histogram: the number of elves whose totals are in
+/// `start..end`. These are wider than the totals, since a wide enough
+/// bucket can end past the biggest `u32`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Bucket {
+	start: u64,
+	end: u64,
+	elves: usize,
+}
+
+/// How the report gets printed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+	Table,
+	Csv,
+}
+
+impl Day01 {
+	/// The total calories each elf is carrying, in input order.
+	fn totals(&self) -> impl Iterator<Item = u32> + '_ {
+		self.elves.iter().map(|snacks| snacks.iter().sum())
+	}
+
+	/// Rank the elves by how many calories they're carrying and return the
+	/// top `k` of them, best first. Ties go to whoever showed up first.
+	///
+	/// If there are fewer than `k` elves, you get all of them.
+	fn top_n(&self, k: usize) -> Vec<RankedElf> {
+		let mut ranking = self
+			.totals()
+			.enumerate()
+			.map(|(index, total)| RankedElf { index, total })
+			.collect::<Vec<_>>();
+		// Sorting is stable, so elves with the same total stay in order.
+		ranking.sort_by_key(|elf| Reverse(elf.total));
+		ranking.truncate(k);
+		ranking
+	}
+
+	/// Crunch the numbers for each elf, in input order.
+	fn stats(&self) -> Vec<ElfStats> {
+		self.elves
+			.iter()
+			.enumerate()
+			.map(|(index, snacks)| {
+				let total = snacks.iter().sum();
+				ElfStats {
+					index,
+					items: snacks.len(),
+					total,
+					// Parsing never makes an elf without snacks, so
+					// there's no dividing by zero here.
+					mean: total as f64 / snacks.len() as f64,
+					largest: snacks.iter().copied().max().unwrap_or(0),
+				}
+			})
+			.collect()
+	}
+
+	/// Sort the elves' totals into buckets `width` calories wide. Every
+	/// bucket from the emptiest elf's to the fullest elf's is there, even
+	/// if nobody's in it, so the gaps show up.
+	fn histogram(&self, width: u32) -> Vec<Bucket> {
+		assert_ne!(width, 0, "Buckets need to be at least a calorie wide");
+		let buckets =
+			self.totals().map(|total| total / width).collect::<Vec<_>>();
+		let (Some(&first), Some(&last)) =
+			(buckets.iter().min(), buckets.iter().max())
+		else {
+			return Vec::new();
+		};
+		let mut counts = vec![0; (last - first) as usize + 1];
+		for bucket in buckets {
+			counts[(bucket - first) as usize] += 1;
+		}
+		counts
+			.into_iter()
+			.zip(first..)
+			.map(|(elves, bucket)| {
+				let start = u64::from(bucket) * u64::from(width);
+				Bucket {
+					start,
+					end: start + u64::from(width),
+					elves,
+				}
+			})
+			.collect()
+	}
+
+	/// Write up the statistics and the histogram.
+	fn report(&self, format: Format, width: u32) -> String {
+		let stats = self.stats();
+		let histogram = self.histogram(width);
+		// Writing to a String can't fail, so the results are ignored.
+		let mut out = String::new();
+		match format {
+			Format::Table => {
+				let _ = writeln!(
+					out,
+					"{:>5} {:>6} {:>8} {:>9} {:>8}",
+					"elf", "snacks", "total", "mean", "largest"
+				);
+				for elf in &stats {
+					let _ = writeln!(
+						out,
+						"{:>5} {:>6} {:>8} {:>9.1} {:>8}",
+						elf.index, elf.items, elf.total, elf.mean, elf.largest
+					);
+				}
+				let _ = writeln!(out);
+				let _ = writeln!(out, "{:>17} {:>5}", "calories", "elves");
+				for bucket in &histogram {
+					let range = format!("{}..{}", bucket.start, bucket.end);
+					let bar = "#".repeat(bucket.elves);
+					let line =
+						format!("{:>17} {:>5} {}", range, bucket.elves, bar);
+					let _ = writeln!(out, "{}", line.trim_end());
+				}
+			}
+			Format::Csv => {
+				let _ = writeln!(out, "elf,snacks,total,mean,largest");
+				for elf in &stats {
+					let _ = writeln!(
+						out,
+						"{},{},{},{:.1},{}",
+						elf.index, elf.items, elf.total, elf.mean, elf.largest
+					);
+				}
+				let _ = writeln!(out);
+				let _ = writeln!(out, "from,to,elves");
+				for bucket in &histogram {
+					let _ = writeln!(
+						out,
+						"{},{},{}",
+						bucket.start, bucket.end, bucket.elves
+					);
+				}
+			}
+		}
+		out
+	}
+}
+
+impl Advent for Day01 {
+	type Answer1 = u32;
+
+	type Answer2 = u32;
+
+	fn parse_input(input: &str) -> Self {
+		parse_reader(input.as_bytes()).expect("Reading from memory failed?")
+	}
+
+	/// If there are no elves at all, nobody is carrying anything.
+	fn part_one(&self) -> u32 {
+		self.top_n(1).iter().map(|elf| elf.total).sum()
+	}
+
+	/// If there are fewer than three elves, this is however many there are.
+	fn part_two(&self) -> u32 {
+		self.top_n(3).iter().map(|elf| elf.total).sum()
+	}
+}
+
+impl StreamingAdvent for Day01 {
+	type State = Inventory;
+
+	fn consume(inventory: &mut Inventory, line: &[u8]) -> ControlFlow<()> {
+		if line.is_empty() {
+			// If the line is empty, we move onto the next elf. Several
+			// empty lines in a row still only make for one gap, though.
+			if !inventory.current.is_empty() {
+				let elf = std::mem::take(&mut inventory.current);
+				inventory.elves.push(elf);
+			}
+		} else {
+			// Otherwise, we add the current snack to the current elf.
+			// Realistically, this should never fail. After all, the input
+			// is pre-prepared and we should be able to trust it. The
+			// infosec people are currently laughing at my naivete.
+			let cal = std::str::from_utf8(line)
+				.ok()
+				.and_then(|line| line.trim().parse::<u32>().ok())
+				.unwrap_or_else(|| {
+					panic!("Invalid value {}", String::from_utf8_lossy(line))
+				});
+			inventory.current.push(cal);
+		}
+		ControlFlow::Continue(())
+	}
+
+	fn finish(mut inventory: Inventory) -> Self {
+		// The last elf doesn't get a blank line after them.
+		if !inventory.current.is_empty() {
+			inventory.elves.push(inventory.current);
+		}
+		Day01 {
+			elves: inventory.elves,
+		}
+	}
+}
+
+fn main() {
+	let args = env::args().skip(1).collect::<Vec<_>>();
+	if args.first().map(String::as_str) == Some("report") {
+		report(&args[1..]);
+		return;
+	}
+	let mut runner = Runner::<Day01>::from_stdin_streaming();
+	println!("The correct elf is {}", runner.part_one());
+	println!("The sum of the top three elves is {}", runner.part_two());
+	runner.report();
+}
+
+/// Handle `report [table|csv] [bucket width]`.
+fn report(args: &[String]) {
+	let usage = || -> ! {
+		eprintln!("Usage: report [table|csv] [bucket width]");
+		process::exit(2);
+	};
+	let format = match args.first().map(String::as_str) {
+		None | Some("table") => Format::Table,
+		Some("csv") => Format::Csv,
+		Some(_) => usage(),
+	};
+	let width = match args.get(1).map(|arg| arg.parse()) {
+		None => BUCKET_WIDTH,
+		Some(Ok(width)) if width > 0 => width,
+		Some(_) => usage(),
+	};
+	if args.len() > 2 {
+		usage();
+	}
+	let puzzle = Day01::parse_input(&input_to_str());
+	print!("{}", puzzle.report(format, width));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n\
+		7000\n8000\n9000\n\n10000";
+
+	#[test]
+	fn test_parse_input() {
+		let expected = Day01 {
+			elves: vec![
+				vec![1000, 2000, 3000],
+				vec![4000],
+				vec![5000, 6000],
+				vec![7000, 8000, 9000],
+				vec![10000],
+			],
+		};
+		assert_eq!(Day01::parse_input(EXAMPLE), expected);
+	}
+
+	#[test]
+	fn test_top_n() {
+		let runner = Day01::parse_input(EXAMPLE);
+
+		let expected = vec![
+			RankedElf {
+				index: 3,
+				total: 24000,
+			},
+			RankedElf {
+				index: 2,
+				total: 11000,
+			},
+			RankedElf {
+				index: 4,
+				total: 10000,
+			},
+		];
+		assert_eq!(runner.top_n(3), expected);
+		assert_eq!(runner.top_n(0), vec![]);
+		assert_eq!(runner.top_n(10).len(), 5);
+	}
+
+	#[test]
+	fn test_parts() {
+		let runner = Day01::parse_input(EXAMPLE);
+		assert_eq!(runner.part_one(), 24000);
+		assert_eq!(runner.part_two(), 45000);
+	}
+
+	#[test]
+	fn test_ties() {
+		let runner = Day01::parse_input("5\n\n7\n\n5\n\n7");
+		let ranking = runner.top_n(4);
+		let indices = ranking.iter().map(|elf| elf.index).collect::<Vec<_>>();
+		assert_eq!(indices, vec![1, 3, 0, 2]);
+	}
+
+	#[test]
+	fn test_edge_cases() {
+		// Nobody showed up.
+		let runner = Day01::parse_input("");
+		assert!(runner.elves.is_empty());
+		assert_eq!(runner.part_one(), 0);
+		assert_eq!(runner.part_two(), 0);
+
+		// Not enough elves for part 2.
+		let runner = Day01::parse_input("100\n\n200");
+		assert_eq!(runner.part_two(), 300);
+
+		// Stray blank lines don't make for extra elves.
+		let runner = Day01::parse_input("\n100\n\n\n\n200\n\n\n");
+		assert_eq!(runner.elves, vec![vec![100], vec![200]]);
+		let runner = Day01::parse_input("100\r\n200\r\n\r\n300\r\n");
+		assert_eq!(runner.totals().collect::<Vec<_>>(), vec![300, 300]);
+	}
+
+	#[test]
+	fn test_stats() {
+		let runner = Day01::parse_input(EXAMPLE);
+		let stats = runner.stats();
+
+		assert_eq!(stats.len(), 5);
+		assert_eq!(
+			stats[2],
+			ElfStats {
+				index: 2,
+				items: 2,
+				total: 11000,
+				mean: 5500.0,
+				largest: 6000,
+			}
+		);
+		assert_eq!(stats[3].mean, 8000.0);
+		assert_eq!(stats[1].largest, 4000);
+	}
+
+	#[test]
+	fn test_histogram() {
+		let runner = Day01::parse_input(EXAMPLE);
+
+		let elves = runner
+			.histogram(5000)
+			.iter()
+			.map(|bucket| (bucket.start, bucket.elves))
+			.collect::<Vec<_>>();
+		// 6000, 4000, 11000, 24000 and 10000, with 15000..20000 empty.
+		let expected =
+			vec![(0, 1), (5000, 1), (10000, 2), (15000, 0), (20000, 1)];
+		assert_eq!(elves, expected);
+		assert_eq!(Day01::parse_input("").histogram(5000), vec![]);
+	}
+
+	#[test]
+	fn test_report_csv() {
+		let runner = Day01::parse_input("1\n2\n\n30");
+		let expected = "elf,snacks,total,mean,largest\n\
+			0,2,3,1.5,2\n\
+			1,1,30,30.0,30\n\
+			\n\
+			from,to,elves\n\
+			0,20,1\n\
+			20,40,1\n";
+		assert_eq!(runner.report(Format::Csv, 20), expected);
+	}
+
+	#[test]
+	fn test_huge_buckets() {
+		let runner = Day01::parse_input(EXAMPLE);
+		let expected = vec![Bucket {
+			start: 0,
+			end: u32::MAX as u64,
+			elves: 5,
+		}];
+		assert_eq!(runner.histogram(u32::MAX), expected);
+		let report = runner.report(Format::Csv, u32::MAX);
+		assert!(report.ends_with("0,4294967295,5\n"), "{}", report);
+	}
+}
@@ -9,18 +9,29 @@
 //!
 //! The second character is "X", "Y", or "Z" and each half of the challenge
 //! interprets it differently. Due to this, the logic has been seperated into
-//! two different functions, named [`guess`] and [`cheat`] for my amusement.
+//! two different strategies, named [`guess`] and [`cheat`] for my amusement.
+//! Each one just decides what to throw, and [`Ruleset::score`] does the
+//! rest.
 //!
 //! The final result is your total score, which is calculated as follows:
 //! 1. Assign points based on what you threw. As TV Tropes would put it, poor
-//!   predictable Rock.
-//!     - Rock is one point.
-//!     - Paper is two points.
-//!     - Scissors is three points.
+//!    predictable Rock.
+//!    - Rock is one point.
+//!    - Paper is two points.
+//!    - Scissors is three points.
 //! 2. Assign points based on your end state.
-//!     - If you won, you get 6 points.
-//!     - If you tied, you get 3 points.
-//!     - If you lost, you get nothing.
+//!    - If you won, you get 6 points.
+//!    - If you tied, you get 3 points.
+//!    - If you lost, you get nothing.
+//!
+//! Rules
+//! -----
+//! The elves play rock paper scissors, but the rules live in a [`Ruleset`],
+//! so they don't have to. Any game where throws beat each other works, and
+//! every throw and outcome has its own points. The puzzle's rules are the
+//! default, and `--param` can swap them for a bigger cyclic game or change
+//! the outcome points: `--param throws=5` plays rock paper scissors lizard
+//! Spock instead, and `--param win=10` makes winning worth more.
 //!
 //! Part 1
 //! ------
@@ -29,205 +40,682 @@
 //! Part 2
 //! ------
 //! See the function [`cheat`].
+//!
+//! Decoding
+//! --------
+//! Who says the elves meant either of those? `decode` tries every way of
+//! reading the second column, as throws or as outcomes, and lists how each
+//! one scores, best first:
+//! `cargo run --bin day02 -- decode < input.txt`.
 
 use std::cmp::Ordering;
-use std::io::stdin;
+use std::env;
+use std::process;
 
-fn main() {
-	let lines = stdin().lines();
-	let mut score = 0;
-	let mut score2 = 0;
-	for line in lines {
-		if let Ok(round) = line {
-			score += guess(round.clone());
-			score2 += cheat(round);
-		} else {
-			panic!("AAAAAAAAAH!")
+use advent::{input_to_str, param_value, Advent, BitSet, Runner};
+
+/// Names for the throws of a cyclic game, in order. Games bigger than this
+/// make up the rest.
+const NAMES: [&str; 5] = ["rock", "paper", "scissors", "spock", "lizard"];
+
+#[derive(Debug)]
+struct Day02 {
+	rounds: Vec<Round>,
+	rules: Ruleset,
+}
+
+/// One line of the strategy guide.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Round {
+	/// What the other elf is going to throw.
+	opponent: Throw,
+	/// The mysterious second column.
+	code: Code,
+}
+
+/// The second column of the guide. What it means depends on who you ask.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Code {
+	X,
+	Y,
+	Z,
+}
+
+/// A throw, as its position in the [`Ruleset`]. The guide only knows about
+/// the first three, which are always rock, paper and scissors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Throw(usize);
+
+impl Throw {
+	const ROCK: Throw = Throw(0);
+	const PAPER: Throw = Throw(1);
+	const SCISSORS: Throw = Throw(2);
+}
+
+/// Something you can throw, and what it's worth.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Shape {
+	name: String,
+	points: u32,
+}
+
+/// The rules of the game: what can be thrown, what beats what, and how many
+/// points everything is worth.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Ruleset {
+	shapes: Vec<Shape>,
+	/// For each throw, the throws it beats.
+	beats: Vec<BitSet>,
+	/// Points for winning a round.
+	win: u32,
+	/// Points for a tie.
+	draw: u32,
+	/// Points for losing. Nothing, unless you're feeling generous.
+	loss: u32,
+}
+
+/// A way to read the second column: either it says what to throw, or how
+/// the round should end. Both are in code order, so X, then Y, then Z.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Decoding {
+	Throws([Throw; 3]),
+	Outcomes([Ordering; 3]),
+}
+
+/// How a bunch of rounds went.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Tally {
+	wins: usize,
+	draws: usize,
+	losses: usize,
+	score: u32,
+}
+
+impl Ruleset {
+	/// Make up a game with these throws, where each `(winner, loser)` pair
+	/// says who beats who. Anything not mentioned is a tie. Throws are worth
+	/// their position, starting from one, and outcomes are scored like the
+	/// puzzle does.
+	fn new(names: &[&str], beats: &[(usize, usize)]) -> Result<Self, String> {
+		if names.len() > BitSet::CAPACITY {
+			return Err(format!("{} throws is too many", names.len()));
 		}
+		let shapes = names
+			.iter()
+			.zip(1..)
+			.map(|(name, points)| Shape {
+				name: name.to_string(),
+				points,
+			})
+			.collect();
+		let mut rules = Ruleset {
+			shapes,
+			beats: vec![BitSet::new(); names.len()],
+			win: 6,
+			draw: 3,
+			loss: 0,
+		};
+		for &(winner, loser) in beats {
+			if winner >= names.len() || loser >= names.len() {
+				return Err(format!("No throw {} or {}", winner, loser));
+			}
+			if winner == loser {
+				return Err(format!("{} can't beat itself", names[winner]));
+			}
+			if rules.beats[loser].contains(winner) {
+				return Err(format!(
+					"{} and {} can't both win",
+					names[winner], names[loser]
+				));
+			}
+			rules.beats[winner].insert(loser);
+		}
+		Ok(rules)
 	}
-	println!("Final score is {}.", score);
-	println!("The final score with cheating is {}.", score2);
-}
 
-/// This function solves the first half of the puzzle, where the second
-/// character in the guide assumes that the second character is the throw
-/// you should make to win.
-/// - "X" is Rock.
-/// - "Y" is Paper.
-/// - "Z" is Scissors.
-///
-/// Therefore, this function concerns itself with calculating score based on
-/// how the game turns out.
-///
-/// The returned value is the answer to part 1.
-fn guess(round: String) -> i32 {
-	let mut score = 0;
-	let res: Vec<&str> = round.split(' ').collect();
-	let (this, that) = (res[0], res[1]);
-	// Figure out what the other elf is throwing.
-	let this = match this {
-		"A" => Throw::Rock,
-		"B" => Throw::Paper,
-		"C" => Throw::Scissors,
-		_ => unreachable!("That's not a valid play!"),
-	};
-	// Figure out what you need to throw according to the guide.
-	// This also increments the score, since we already know what we should
-	// be adding to it.
-	let that = match that {
-		"X" => {
-			score += 1;
-			Throw::Rock
+	/// A fair game with `count` throws in a circle, where every throw beats
+	/// half of the others and loses to the rest. That only works with an odd
+	/// number of them. Three is rock paper scissors, five adds Spock and
+	/// lizard.
+	///
+	/// Going around the circle, each throw beats the ones an odd number of
+	/// steps behind it.
+	fn cyclic(count: usize) -> Result<Self, String> {
+		// Check this before working out every pair of throws, rather than
+		// after.
+		if count > BitSet::CAPACITY {
+			return Err(format!("{} throws is too many", count));
 		}
-		"Y" => {
-			score += 2;
-			Throw::Paper
+		if count.is_multiple_of(2) {
+			return Err(format!(
+				"A fair game needs an odd number of throws, not {}",
+				count
+			));
 		}
-		"Z" => {
-			score += 3;
-			Throw::Scissors
+		let names = (0..count)
+			.map(|i| match NAMES.get(i) {
+				Some(name) => name.to_string(),
+				None => format!("throw {}", i + 1),
+			})
+			.collect::<Vec<_>>();
+		let names = names.iter().map(String::as_str).collect::<Vec<_>>();
+		let beats = (0..count)
+			.flat_map(|winner| (0..count).map(move |loser| (winner, loser)))
+			.filter(|(winner, loser)| (winner + count - loser) % count % 2 == 1)
+			.collect::<Vec<_>>();
+		Self::new(&names, &beats)
+	}
+
+	/// The rules from the puzzle.
+	fn rock_paper_scissors() -> Self {
+		Self::cyclic(3).expect("Three is odd")
+	}
+
+	/// How a round goes for whoever threw `ours`.
+	///
+	/// * [`Ordering::Greater`] represents a victory.
+	/// * [`Ordering::Equal`] represents a tie.
+	/// * [`Ordering::Less`] represents a loss.
+	fn result(&self, ours: Throw, theirs: Throw) -> Ordering {
+		if self.beats[ours.0].contains(theirs.0) {
+			Ordering::Greater
+		} else if self.beats[theirs.0].contains(ours.0) {
+			Ordering::Less
+		} else {
+			Ordering::Equal
 		}
-		_ => unreachable!("What are you doing?"),
-	};
-	// Increment the score by the result of the match.
-	match that.result(&this) {
-		Ordering::Less => {
-			// No point in incrementing an empty score.
-			// score += 0;
+	}
+
+	/// Fulfill the requirements added by part 2. Figure out what to throw
+	/// against `theirs` to get `result`. If there's more than one way to do
+	/// that, go for the one worth the most points. If there's no way at all,
+	/// there's no answer.
+	fn respond(&self, theirs: Throw, result: Ordering) -> Option<Throw> {
+		(0..self.shapes.len())
+			.map(Throw)
+			.filter(|&ours| self.result(ours, theirs) == result)
+			// Ties go to the first throw, so iterate backwards for max_by_key.
+			.rev()
+			.max_by_key(|ours| self.shapes[ours.0].points)
+	}
+
+	/// How many points the end state of a round is worth.
+	fn outcome_score(&self, result: Ordering) -> u32 {
+		match result {
+			Ordering::Less => self.loss,
+			Ordering::Equal => self.draw,
+			Ordering::Greater => self.win,
 		}
-		Ordering::Equal => {
-			score += 3;
+	}
+
+	/// The score for throwing `ours` against `theirs`: points for the throw
+	/// itself, plus points for how it went.
+	fn score(&self, ours: Throw, theirs: Throw) -> u32 {
+		self.shapes[ours.0].points
+			+ self.outcome_score(self.result(ours, theirs))
+	}
+}
+
+impl Decoding {
+	/// How part 1 reads the guide.
+	const GUESS: Decoding =
+		Decoding::Throws([Throw::ROCK, Throw::PAPER, Throw::SCISSORS]);
+
+	/// How part 2 reads the guide.
+	const CHEAT: Decoding = Decoding::Outcomes([
+		Ordering::Less,
+		Ordering::Equal,
+		Ordering::Greater,
+	]);
+
+	/// Every way the second column could be read under these rules. Each
+	/// code gets a different meaning, so for three throws that's six ways
+	/// to pick throws and six ways to pick outcomes.
+	fn all(rules: &Ruleset) -> Vec<Decoding> {
+		let outcomes = [Ordering::Less, Ordering::Equal, Ordering::Greater];
+		let throws = arrangements(rules.shapes.len())
+			.map(|codes| Decoding::Throws(codes.map(Throw)));
+		let outcomes = arrangements(outcomes.len())
+			.map(|codes| Decoding::Outcomes(codes.map(|i| outcomes[i])));
+		throws.chain(outcomes).collect()
+	}
+
+	/// Work out what to throw in a round, reading the guide this way.
+	fn decode(&self, rules: &Ruleset, round: &Round) -> Throw {
+		let code = round.code as usize;
+		match self {
+			Decoding::Throws(throws) => throws[code],
+			Decoding::Outcomes(outcomes) => {
+				let result = outcomes[code];
+				// With rules made up from `Ruleset::new`, this might not be
+				// possible.
+				rules.respond(round.opponent, result).unwrap_or_else(|| {
+					let name = &rules.shapes[round.opponent.0].name;
+					panic!("Nothing gets {:?} against {}", result, name)
+				})
+			}
 		}
-		Ordering::Greater => {
-			score += 6;
+	}
+
+	/// Spell out what each code means, like `X=rock Y=paper Z=scissors`.
+	fn describe(&self, rules: &Ruleset) -> String {
+		let meanings = match self {
+			Decoding::Throws(throws) => {
+				throws.map(|throw| rules.shapes[throw.0].name.as_str())
+			}
+			Decoding::Outcomes(outcomes) => {
+				outcomes.map(|result| match result {
+					Ordering::Less => "lose",
+					Ordering::Equal => "draw",
+					Ordering::Greater => "win",
+				})
+			}
+		};
+		format!("X={} Y={} Z={}", meanings[0], meanings[1], meanings[2])
+	}
+}
+
+/// Every way to pick three different things out of `count`, in order.
+fn arrangements(count: usize) -> impl Iterator<Item = [usize; 3]> {
+	(0..count).flat_map(move |x| {
+		(0..count).flat_map(move |y| {
+			(0..count)
+				.filter(move |&z| x != y && y != z && x != z)
+				.map(move |z| [x, y, z])
+		})
+	})
+}
+
+impl Day02 {
+	/// Play every round the way `strategy` says to, and keep score.
+	fn play<F>(&self, strategy: F) -> Tally
+	where
+		F: Fn(&Ruleset, &Round) -> Throw,
+	{
+		let mut tally = Tally::default();
+		for round in &self.rounds {
+			let ours = strategy(&self.rules, round);
+			match self.rules.result(ours, round.opponent) {
+				Ordering::Less => tally.losses += 1,
+				Ordering::Equal => tally.draws += 1,
+				Ordering::Greater => tally.wins += 1,
+			}
+			tally.score += self.rules.score(ours, round.opponent);
 		}
-	};
-	score
+		tally
+	}
+
+	/// Play the guide with every possible [`Decoding`], best score first.
+	fn search(&self) -> Vec<(Decoding, Tally)> {
+		let mut results = Decoding::all(&self.rules)
+			.into_iter()
+			.map(|decoding| {
+				let tally =
+					self.play(|rules, round| decoding.decode(rules, round));
+				(decoding, tally)
+			})
+			.collect::<Vec<_>>();
+		results.sort_by_key(|(_, tally)| std::cmp::Reverse(tally.score));
+		results
+	}
+}
+
+impl Advent for Day02 {
+	type Answer1 = u32;
+
+	type Answer2 = u32;
+
+	const PARAMS: &'static [(&'static str, i64)] =
+		&[("throws", 3), ("win", 6), ("draw", 3), ("loss", 0)];
+
+	fn parse_input(input: &str) -> Self {
+		let rounds = input
+			.lines()
+			.enumerate()
+			.map(|(number, line)| {
+				Round::parse(line).unwrap_or_else(|err| {
+					panic!("Line {}: {} in {:?}", number + 1, err, line)
+				})
+			})
+			.collect();
+		Day02 {
+			rounds,
+			rules: Ruleset::rock_paper_scissors(),
+		}
+	}
+
+	fn part_one(&self) -> u32 {
+		self.play(guess).score
+	}
+
+	fn part_two(&self) -> u32 {
+		self.play(cheat).score
+	}
+
+	fn set_param(&mut self, name: &str, value: i64) -> Result<(), String> {
+		match name {
+			"throws" => {
+				let count = param_value::<usize>(name, value)?;
+				// The guide needs rock, paper and scissors to be there.
+				if count < 3 {
+					return Err(format!(
+						"The guide needs 3 throws, not {}",
+						count
+					));
+				}
+				if count > BitSet::CAPACITY {
+					return Err(format!("{} throws is too many", count));
+				}
+				let rules = &self.rules;
+				self.rules = Ruleset {
+					win: rules.win,
+					draw: rules.draw,
+					loss: rules.loss,
+					..Ruleset::cyclic(count)?
+				};
+			}
+			"win" => self.rules.win = param_value(name, value)?,
+			"draw" => self.rules.draw = param_value(name, value)?,
+			"loss" => self.rules.loss = param_value(name, value)?,
+			_ => return Err(format!("Unknown parameter {}", name)),
+		}
+		Ok(())
+	}
 }
 
-/// Now the fun part. This part assumes the second character is how you need to
-/// _throw the match_ to get the optimal score.
-/// - "X" means you win.
+impl Round {
+	/// Read a round like `A Y`.
+	fn parse(line: &str) -> Result<Self, String> {
+		let (opponent, code) = line
+			.split_once(' ')
+			.ok_or_else(|| "Expected two letters".to_owned())?;
+		// Figure out what the other elf is throwing.
+		let opponent = match opponent {
+			"A" => Throw::ROCK,
+			"B" => Throw::PAPER,
+			"C" => Throw::SCISSORS,
+			// Hey, they're not supposed to cheat!
+			_ => return Err(format!("{:?} isn't a valid play", opponent)),
+		};
+		let code = match code {
+			"X" => Code::X,
+			"Y" => Code::Y,
+			"Z" => Code::Z,
+			_ => return Err(format!("What are you doing with {:?}?", code)),
+		};
+		Ok(Round { opponent, code })
+	}
+}
+
+/// This strategy solves the first half of the puzzle, where the second
+/// character in the guide is assumed to be the throw you should make to win.
+/// - "X" is Rock.
+/// - "Y" is Paper.
+/// - "Z" is Scissors.
+///
+/// Summed over the guide, this is the answer to part 1.
+fn guess(rules: &Ruleset, round: &Round) -> Throw {
+	Decoding::GUESS.decode(rules, round)
+}
+
+/// Now the fun part. This strategy assumes the second character is how you
+/// need to _throw the match_ to get the optimal score.
+/// - "X" means you lose.
 /// - "Y" means you tie.
-/// - "Z" means you lose.
+/// - "Z" means you win.
 ///
-/// This function therefore concerns itself with figuring out what to respond
-/// with to achieve the ideal victory conditions.
+/// This strategy therefore concerns itself with figuring out what to respond
+/// with to achieve the ideal victory conditions. Pun intended.
 ///
-/// The returned value is the answer to part 2.
+/// Summed over the guide, this is the answer to part 2.
 ///
 /// Side note
 /// ---------
-/// I wonder if it's guaranteed that this secondary half always results in
+/// I wondered if it's guaranteed that this secondary half always results in
 /// a greater number than the first half. After all, if it didn't, there's no
-/// point in being this sneaky.
-fn cheat(round: String) -> i32 {
-	let mut score = 0;
-	// I wonder if in Python this would be one line instead of two.
-	let res: Vec<&str> = round.split(' ').collect();
-	let (this, that) = (res[0], res[1]);
-	// Figure out what the other guy is doing. This is the same as in
-	// `guess`, except with a snarkier response to invalid input.
-	let this = match this {
-		"A" => Throw::Rock,
-		"B" => Throw::Paper,
-		"C" => Throw::Scissors,
-		_ => unreachable!("Hey, they're not supposed to cheat!"),
-	};
-	// Figure out how the strategy guide says we should throw the match.
-	// Given that we already know the result, we can bump up the score here.
-	let result = match that {
-		"X" => {
-			// No point in incrementing an empty score.
-			// Other than maybe keeping up appearances of fairness...
-			// score += 0;
-			Ordering::Less
-		}
-		"Y" => {
-			score += 3;
-			Ordering::Equal
-		}
-		"Z" => {
-			score += 6;
-			Ordering::Greater
-		}
-		_ => unreachable!("Uh, give up I guess?"),
-	};
-	// Figure out what throw will throw the match. Pun intended.
-	// Adjust the score accordingly.
-	match this.cheat(result) {
-		Throw::Rock => {
-			score += 1;
-		}
-		Throw::Paper => {
-			score += 2;
-		}
-		Throw::Scissors => {
-			score += 3;
+/// point in being this sneaky. Turns out it isn't: the example scores 15 the
+/// first way and 12 this way. `decode` will tell you how your guide does.
+fn cheat(rules: &Ruleset, round: &Round) -> Throw {
+	Decoding::CHEAT.decode(rules, round)
+}
+
+fn main() {
+	let args = env::args().skip(1).collect::<Vec<_>>();
+	if args.first().map(String::as_str) == Some("decode") {
+		if args.len() > 1 {
+			eprintln!("Usage: decode");
+			process::exit(2);
 		}
+		decode();
+		return;
 	}
-	score
+	let mut runner = Runner::<Day02>::from_stdin();
+	println!("Final score is {}.", runner.part_one());
+	println!("The final score with cheating is {}.", runner.part_two());
+	runner.report();
 }
 
-#[derive(PartialEq, Eq, Debug)]
-/// Represents a Rock Paper Scissors throw.
-enum Throw {
-	Rock,
-	Paper,
-	Scissors,
+/// Handle `decode`, by printing how every reading of the guide scores.
+fn decode() {
+	let puzzle = Day02::parse_input(&input_to_str());
+	let results = puzzle.search();
+	println!(
+		"{:<36} {:>6} {:>6} {:>6} {:>8}",
+		"decoding", "wins", "draws", "losses", "score"
+	);
+	for (decoding, tally) in &results {
+		let mut name = decoding.describe(&puzzle.rules);
+		if *decoding == Decoding::GUESS {
+			name += " (part 1)";
+		} else if *decoding == Decoding::CHEAT {
+			name += " (part 2)";
+		}
+		println!(
+			"{:<36} {:>6} {:>6} {:>6} {:>8}",
+			name, tally.wins, tally.draws, tally.losses, tally.score
+		);
+	}
+	// There's always at least the two the puzzle uses.
+	let (best, best_tally) = results[0];
+	let (worst, worst_tally) = results[results.len() - 1];
+	println!();
+	println!(
+		"Best is {} with {}.",
+		best.describe(&puzzle.rules),
+		best_tally.score
+	);
+	println!(
+		"Worst is {} with {}.",
+		worst.describe(&puzzle.rules),
+		worst_tally.score
+	);
+	let (one, two) = (puzzle.part_one(), puzzle.part_two());
+	let verdict = match two.cmp(&one) {
+		Ordering::Greater => "beats",
+		Ordering::Equal => "ties with",
+		Ordering::Less => "loses to",
+	};
+	println!("Part 2 ({}) {} part 1 ({}).", two, verdict, one);
 }
 
-impl Throw {
-	/// I was going to use [`PartialOrd`] for this, but that seemed a bit too
-	/// much like I was abusing the mechanism. So I did this. Same enum, but
-	/// not using the operators in bad ways.
-	///
-	/// * [`Ordering::Greater`] represents a victory.
-	/// * [`Ordering::Equal`] represents a tie.
-	/// * [`Ordering::Less`] represents a loss.
-	fn result(&self, other: &Throw) -> Ordering {
-		match self {
-			Throw::Rock => match other {
-				Throw::Rock => Ordering::Equal,
-				Throw::Paper => Ordering::Less,
-				Throw::Scissors => Ordering::Greater,
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use advent::set_param;
+
+	const EXAMPLE: &str = "A Y\nB X\nC Z";
+
+	#[test]
+	fn test_parse_input() {
+		let runner = Day02::parse_input(EXAMPLE);
+
+		let expected = vec![
+			Round {
+				opponent: Throw::ROCK,
+				code: Code::Y,
 			},
-			Throw::Paper => match other {
-				Throw::Rock => Ordering::Greater,
-				Throw::Paper => Ordering::Equal,
-				Throw::Scissors => Ordering::Less,
+			Round {
+				opponent: Throw::PAPER,
+				code: Code::X,
 			},
-			Throw::Scissors => match other {
-				Throw::Rock => Ordering::Less,
-				Throw::Paper => Ordering::Greater,
-				Throw::Scissors => Ordering::Equal,
+			Round {
+				opponent: Throw::SCISSORS,
+				code: Code::Z,
 			},
+		];
+		assert_eq!(runner.rounds, expected);
+	}
+
+	#[test]
+	fn test_parts() {
+		let runner = Day02::parse_input(EXAMPLE);
+		assert_eq!(runner.part_one(), 15);
+		assert_eq!(runner.part_two(), 12);
+	}
+
+	#[test]
+	fn test_respond_gets_what_it_wants() {
+		for count in [3, 5, 7] {
+			let rules = Ruleset::cyclic(count).unwrap();
+			let results = [Ordering::Less, Ordering::Equal, Ordering::Greater];
+			for theirs in (0..count).map(Throw) {
+				for result in results {
+					let ours = rules.respond(theirs, result).unwrap();
+					assert_eq!(rules.result(ours, theirs), result);
+				}
+			}
 		}
 	}
-	/// Fulfill the requirements added by part 2. Again, the ordering
-	/// represents the result of the game, and the returned value is what you
-	/// need to throw in response.
-	///
-	/// * [`Ordering::Less`] results in the value you need to LOSE.
-	/// * [`Ordering::Equal`] results in the value you need to TIE.
-	/// * [`Ordering::Greater`] results in the value you need to WIN.
-	fn cheat(&self, result: Ordering) -> Self {
-		match self {
-			Throw::Rock => match result {
-				Ordering::Less => Throw::Scissors,
-				Ordering::Equal => Throw::Rock,
-				Ordering::Greater => Throw::Paper,
-			},
-			Throw::Paper => match result {
-				Ordering::Less => Throw::Rock,
-				Ordering::Equal => Throw::Paper,
-				Ordering::Greater => Throw::Scissors,
-			},
-			Throw::Scissors => match result {
-				Ordering::Less => Throw::Paper,
-				Ordering::Equal => Throw::Scissors,
-				Ordering::Greater => Throw::Rock,
-			},
+
+	#[test]
+	fn test_lizard_spock() {
+		let rules = Ruleset::cyclic(5).unwrap();
+		let throw = |name| {
+			let position = rules.shapes.iter().position(|s| s.name == name);
+			Throw(position.unwrap())
+		};
+
+		// As Sheldon explains it.
+		let wins = [
+			("scissors", "paper"),
+			("paper", "rock"),
+			("rock", "lizard"),
+			("lizard", "spock"),
+			("spock", "scissors"),
+			("scissors", "lizard"),
+			("lizard", "paper"),
+			("paper", "spock"),
+			("spock", "rock"),
+			("rock", "scissors"),
+		];
+		for (winner, loser) in wins {
+			let (winner, loser) = (throw(winner), throw(loser));
+			assert_eq!(rules.result(winner, loser), Ordering::Greater);
+			assert_eq!(rules.result(loser, winner), Ordering::Less);
 		}
+		assert_eq!(rules.beats.iter().map(BitSet::len).sum::<usize>(), 10);
+	}
+
+	#[test]
+	fn test_bad_rules() {
+		assert!(Ruleset::cyclic(4).is_err());
+		assert!(Ruleset::new(&["a", "b"], &[(0, 0)]).is_err());
+		assert!(Ruleset::new(&["a", "b"], &[(0, 1), (1, 0)]).is_err());
+		assert!(Ruleset::new(&["a", "b"], &[(0, 2)]).is_err());
+	}
+
+	#[test]
+	fn test_custom_rules() {
+		// Rock always wins, and nothing else matters.
+		let mut rules =
+			Ruleset::new(&["rock", "paper", "scissors"], &[(0, 1), (0, 2)])
+				.unwrap();
+		rules.win = 10;
+		rules.shapes[0].points = 0;
+		let mut runner = Day02::parse_input(EXAMPLE);
+		runner.rules = rules;
+
+		// Paper loses to rock, a pointless rock beats paper, and scissors
+		// ties scissors.
+		assert_eq!(runner.part_one(), 2 + 10 + (3 + 3));
+	}
+
+	#[test]
+	fn test_params() {
+		let mut runner = Day02::parse_input(EXAMPLE);
+		set_param(&mut runner, "win", 10).unwrap();
+		assert_eq!(runner.part_one(), 15 + 4);
+
+		// Spock is worth more than rock, and beats scissors just as well.
+		set_param(&mut runner, "throws", 5).unwrap();
+		assert_eq!(runner.rules.win, 10);
+		assert_eq!(runner.part_two(), 4 + 4 + 14);
+		assert!(set_param(&mut runner, "throws", 1).is_err());
+		assert!(set_param(&mut runner, "throws", 6).is_err());
+		// Too many to fit in a BitSet, which should be turned away before
+		// anything works out a trillion pairs of throws.
+		assert!(set_param(&mut runner, "throws", 129).is_err());
+		assert!(set_param(&mut runner, "throws", 1_000_001).is_err());
+		assert!(Ruleset::cyclic(1_000_001).is_err());
+		assert!(Ruleset::cyclic(127).is_ok());
+	}
+
+	#[test]
+	fn test_decodings() {
+		let rules = Ruleset::rock_paper_scissors();
+		let all = Decoding::all(&rules);
+		assert_eq!(all.len(), 12);
+		assert!(all.contains(&Decoding::GUESS));
+		assert!(all.contains(&Decoding::CHEAT));
+		// No two decodings are the same.
+		for (i, a) in all.iter().enumerate() {
+			assert!(!all[i + 1..].contains(a));
+		}
+		// Three codes for five throws is 5 * 4 * 3 ways, plus the outcomes.
+		assert_eq!(Decoding::all(&Ruleset::cyclic(5).unwrap()).len(), 66);
+		assert_eq!(
+			Decoding::CHEAT.describe(&rules),
+			"X=lose Y=draw Z=win".to_owned()
+		);
+	}
+
+	#[test]
+	fn test_search() {
+		let runner = Day02::parse_input(EXAMPLE);
+		let results = runner.search();
+
+		let scores = results.iter().map(|(_, t)| t.score).collect::<Vec<_>>();
+		assert!(scores.windows(2).all(|pair| pair[0] >= pair[1]));
+		// X faces paper, Y faces rock and Z faces scissors, so always
+		// winning scores 3 + 2 + 1 + 3 * 6.
+		let (best, tally) = results[0];
+		let throws = [Throw::SCISSORS, Throw::PAPER, Throw::ROCK];
+		assert_eq!(best, Decoding::Throws(throws));
+		assert_eq!(tally.wins, 3);
+		assert_eq!(tally.score, 24);
+		let guess = results.iter().find(|(d, _)| *d == Decoding::GUESS);
+		assert_eq!(
+			guess.unwrap().1,
+			Tally {
+				wins: 1,
+				draws: 1,
+				losses: 1,
+				score: 15,
+			}
+		);
+	}
+
+	#[test]
+	fn test_bad_rounds() {
+		assert!(Round::parse("A").is_err());
+		assert!(Round::parse("D X").is_err());
+		assert!(Round::parse("A W").is_err());
+		assert!(Round::parse("a x").is_err());
+	}
+
+	#[test]
+	#[should_panic(expected = "Line 2")]
+	fn test_bad_input() {
+		Day02::parse_input("A Y\nB Q");
 	}
 }
@@ -11,72 +11,281 @@
 //! Part 2
 //! ------
 //! For every three elves, find the item that all three of them have packed.
-//! This is the identification badge for that triplet of elves.
+//! This is the identification badge for that triplet of elves. Nothing about
+//! that actually needs it to be three, so [`Day03::common_items`] takes any
+//! group size.
+//!
+//! Audit
+//! -----
+//! Not part of the puzzle, but somebody has to check the elves' work.
+//! `cargo run --bin day03 -- audit < input.txt` lists, for each rucksack,
+//! every item that ended up in both compartments, and calls out rucksacks
+//! that can't be split evenly or have things in them that aren't items.
+//! After that comes how much priority each misplaced item adds up to.
 
-use std::collections::HashSet;
-use std::io::stdin;
+use std::env;
+use std::process;
 
-fn main() {
-	let lines = stdin().lines();
-	let mut priority = 0;
-	let mut badge_priority = 0;
-	let mut triplet = vec![];
-	'search: for line in lines {
-		if let Ok(sack) = line {
-			// Prep for part 2.
-			triplet.push(sack.clone());
-			// Check to see if we have a triplet.
-			if triplet.len() == 3 {
-				// Even though this code is part 2, it has to be run first
-				// since success on the first part goes back to the start
-				// of the loop.
-				let first = &triplet[0];
-				let mut badge_set: HashSet<char> = HashSet::new();
-				for elf in triplet.iter() {
-					let chars = elf.chars();
-					if elf == first {
-						// If this is the first run through, just dump
-						// everything in the list of candidates.
-						badge_set = chars.collect();
-					} else {
-						// Otherwise filter everything in the list of candidates
-						// that doesn't exist in this elf's sack.
-						let items: HashSet<char> = chars.collect();
-						for item in badge_set.clone() {
-							if !items.contains(&item) {
-								badge_set.remove(&item);
-							}
-						}
-					}
+use advent::{input_to_str, Advent, BitSet, Runner};
+
+#[derive(Debug)]
+struct Day03 {
+	rucksacks: Vec<Rucksack>,
+}
+
+/// One elf's packing job.
+#[derive(Debug)]
+struct Rucksack {
+	/// Everything in the first compartment, as ASCII codes.
+	upper: BitSet,
+	/// Everything in the second compartment, as ASCII codes.
+	lower: BitSet,
+	/// How many things were packed. If it's odd, the second compartment got
+	/// the extra one.
+	length: usize,
+	/// Anything that isn't a letter, and so isn't an item. These don't go
+	/// in either compartment.
+	strays: Vec<char>,
+}
+
+/// What the audit found in a rucksack.
+#[derive(Debug, PartialEq, Eq)]
+struct Finding {
+	/// Which line of input the rucksack was on, starting from one.
+	line: usize,
+	/// Every item that's in both compartments.
+	misplaced: Vec<char>,
+	/// Whether the compartments couldn't be the same size.
+	odd: bool,
+	/// Anything that isn't an item.
+	strays: Vec<char>,
+}
+
+impl Rucksack {
+	/// Everything in the rucksack, whichever compartment it's in.
+	fn items(&self) -> BitSet {
+		self.upper | self.lower
+	}
+}
+
+impl Day03 {
+	/// Complain about the first rucksack that has something in it that isn't
+	/// an item, since the puzzle can't be solved with those.
+	fn check_strays(&self) -> Result<(), String> {
+		match self.rucksacks.iter().position(|s| !s.strays.is_empty()) {
+			Some(i) => Err(format!(
+				"Line {} has {:?}, which isn't an item",
+				i + 1,
+				self.rucksacks[i].strays[0]
+			)),
+			None => Ok(()),
+		}
+	}
+
+	/// The item packed in both compartments of each rucksack.
+	fn misplaced(&self) -> Result<Vec<char>, String> {
+		self.check_strays()?;
+		self.rucksacks
+			.iter()
+			.enumerate()
+			.map(|(i, sack)| {
+				only_item(sack.upper & sack.lower)
+					.map_err(|err| format!("Rucksack {} has {}", i + 1, err))
+			})
+			.collect()
+	}
+
+	/// Split the rucksacks into groups of `group_size` elves, in order, and
+	/// find the one item everyone in each group has.
+	///
+	/// It's an error if the rucksacks don't split evenly, or if a group
+	/// doesn't have exactly one item in common.
+	fn common_items(&self, group_size: usize) -> Result<Vec<char>, String> {
+		if group_size == 0 {
+			return Err("Groups need at least one elf".to_owned());
+		}
+		self.check_strays()?;
+		self.rucksacks
+			.chunks(group_size)
+			.enumerate()
+			.map(|(i, group)| {
+				if group.len() < group_size {
+					return Err(format!(
+						"Group {} only has {} of {} elves",
+						i + 1,
+						group.len(),
+						group_size
+					));
 				}
-				// If everything went okay, there should only ever be one item
-				// in the set at this point.
-				assert!(
-					badge_set.len() == 1,
-					"We found counterfeit badges: {:?}",
-					badge_set
-				);
-				let badges: Vec<_> = badge_set.into_iter().collect();
-				badge_priority += priority_value(badges[0]);
-			}
-			let (upper, lower) = sack.split_at(sack.len() / 2);
-			for item in upper.chars() {
-				// If this were Python, I could just do `item in lower`,
-				// but Rust doesn't think that's safe or necessary. Welp.
-				if lower.find(item).is_some() {
-					priority += priority_value(item);
-					// This shortcuts the search. After all, there should only
-					// ever be one item shared between the two halves.
-					continue 'search;
+				// Start with everything and whittle it down.
+				let shared = group
+					.iter()
+					.map(Rucksack::items)
+					.fold(group[0].items(), |acc, items| acc & items);
+				only_item(shared)
+					.map_err(|err| format!("Group {} has {}", i + 1, err))
+			})
+			.collect()
+	}
+
+	/// Go through every rucksack and write down everything wrong with it.
+	/// Unlike [`Day03::misplaced`], this doesn't stop at one item per
+	/// rucksack, or at the first bad one.
+	fn audit(&self) -> Vec<Finding> {
+		self.rucksacks
+			.iter()
+			.enumerate()
+			.map(|(i, sack)| Finding {
+				line: i + 1,
+				misplaced: to_chars(sack.upper & sack.lower).collect(),
+				odd: sack.length % 2 == 1,
+				strays: sack.strays.clone(),
+			})
+			.collect()
+	}
+}
+
+/// Add up how much priority each misplaced item is worth across the whole
+/// audit. Returns each item with how many rucksacks it's misplaced in and
+/// the priority that adds up to, in priority order.
+fn priority_totals(findings: &[Finding]) -> Vec<(char, usize, u32)> {
+	// Priorities go from 1 to 52, so slot 0 just sits there.
+	let mut counts = [0; 53];
+	for item in findings.iter().flat_map(|f| &f.misplaced) {
+		if let Some(priority) = priority_value(*item) {
+			counts[priority as usize] += 1;
+		}
+	}
+	('a'..='z')
+		.chain('A'..='Z')
+		.zip(1..)
+		.filter(|&(_, priority)| counts[priority as usize] > 0)
+		.map(|(item, priority)| {
+			let count = counts[priority as usize];
+			(item, count, priority * count as u32)
+		})
+		.collect()
+}
+
+/// Turn a set of ASCII codes back into characters.
+fn to_chars(items: BitSet) -> impl Iterator<Item = char> {
+	items.into_iter().map(|item| item as u8 as char)
+}
+
+/// Get the one and only item in a set, complaining if there isn't exactly
+/// one of them.
+fn only_item(items: BitSet) -> Result<char, String> {
+	let mut iter = to_chars(items);
+	match (iter.next(), iter.next()) {
+		(Some(item), None) => Ok(item),
+		(None, _) => Err("nothing in common".to_owned()),
+		// We found counterfeit badges.
+		_ => Err(format!(
+			"more than one item in common: {:?}",
+			to_chars(items).collect::<String>()
+		)),
+	}
+}
+
+impl Advent for Day03 {
+	type Answer1 = u32;
+
+	type Answer2 = u32;
+
+	fn parse_input(input: &str) -> Self {
+		let rucksacks = input
+			.lines()
+			.map(|line| {
+				let contents = line.chars().collect::<Vec<_>>();
+				let (upper, lower) = contents.split_at(contents.len() / 2);
+				let items = |half: &[char]| {
+					half.iter()
+						.filter(|c| c.is_ascii_alphabetic())
+						.map(|&c| c as usize)
+						.collect()
+				};
+				Rucksack {
+					upper: items(upper),
+					lower: items(lower),
+					length: contents.len(),
+					strays: contents
+						.iter()
+						.copied()
+						.filter(|c| !c.is_ascii_alphabetic())
+						.collect(),
 				}
-			}
-		} else {
-			panic!("Input has failed! AAAAAAAAAAH!")
+			})
+			.collect();
+		Day03 { rucksacks }
+	}
+
+	fn part_one(&self) -> u32 {
+		let items = self.misplaced().unwrap_or_else(|err| panic!("{}", err));
+		items.into_iter().filter_map(priority_value).sum()
+	}
+
+	fn part_two(&self) -> u32 {
+		let badges =
+			self.common_items(3).unwrap_or_else(|err| panic!("{}", err));
+		badges.into_iter().filter_map(priority_value).sum()
+	}
+}
+
+fn main() {
+	let args = env::args().skip(1).collect::<Vec<_>>();
+	if args.first().map(String::as_str) == Some("audit") {
+		if args.len() > 1 {
+			eprintln!("Usage: audit");
+			process::exit(2);
 		}
+		audit();
+		return;
 	}
+	let mut runner = Runner::<Day03>::from_stdin();
 	// Print the solutions to the puzzles.
-	println!("Final sum of priorities is {}", priority);
-	println!("Final sum of badge priority is {}", badge_priority);
+	println!("Final sum of priorities is {}", runner.part_one());
+	println!("Final sum of badge priority is {}", runner.part_two());
+	runner.report();
+}
+
+/// Handle `audit`, by printing everything wrong with every rucksack.
+fn audit() {
+	let puzzle = Day03::parse_input(&input_to_str());
+	let findings = puzzle.audit();
+	println!("{:>6} {:>6}  {:<12} notes", "line", "length", "misplaced");
+	for (finding, sack) in findings.iter().zip(&puzzle.rucksacks) {
+		let mut notes = Vec::new();
+		if finding.odd {
+			notes.push("odd length".to_owned());
+		}
+		if !finding.strays.is_empty() {
+			notes.push(format!(
+				"not items: {:?}",
+				finding.strays.iter().collect::<String>()
+			));
+		}
+		let misplaced = match finding.misplaced.len() {
+			0 => "-".to_owned(),
+			_ => finding.misplaced.iter().collect(),
+		};
+		let line = format!(
+			"{:>6} {:>6}  {:<12} {}",
+			finding.line,
+			sack.length,
+			misplaced,
+			notes.join(", ")
+		);
+		println!("{}", line.trim_end());
+	}
+
+	let totals = priority_totals(&findings);
+	println!();
+	println!("{:>4} {:>6} {:>8}", "item", "count", "priority");
+	for (item, count, priority) in &totals {
+		println!("{:>4} {:>6} {:>8}", item, count, priority);
+	}
+	let sum = totals.iter().map(|(_, _, priority)| priority).sum::<u32>();
+	println!("{:>4} {:>6} {:>8}", "all", "", sum);
 }
 
 /// Find the priority value of an item. This is mainly for inputting
@@ -84,15 +293,104 @@ fn main() {
 ///
 /// Priority value is determined as follows:
 /// - Lowercase letters are their position in the alphabet, such that 'a' is 1
-///     and 'z' is 26.
+///   and 'z' is 26.
 /// - Uppercase values are their position in the alphabet _plus 26_
-///     to differentiate them from lowercase letters, such that 'A' is 27
-///     and 'Z' is 52.
-fn priority_value(item: char) -> u32 {
+///   to differentiate them from lowercase letters, such that 'A' is 27
+///   and 'Z' is 52.
+///
+/// Anything else isn't an item, so it doesn't have a priority.
+fn priority_value(item: char) -> Option<u32> {
 	let prior = item as u32;
 	match item {
-		'a'..='z' => prior - 96,
-		'A'..='Z' => prior - 38,
-		_ => unreachable!("That shouldn't be here..."),
+		'a'..='z' => Some(prior - 96),
+		'A'..='Z' => Some(prior - 38),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp\n\
+		jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\n\
+		PmmdzqPrVvPwwTWBwg\n\
+		wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn\n\
+		ttgJtRGJQctTZtZT\n\
+		CrZsJsPPZsGzwwsLwLmpwMDw";
+
+	#[test]
+	fn test_parts() {
+		let runner = Day03::parse_input(EXAMPLE);
+		assert_eq!(runner.misplaced(), Ok("pLPvts".chars().collect()));
+		assert_eq!(runner.part_one(), 157);
+		assert_eq!(runner.part_two(), 70);
+	}
+
+	#[test]
+	fn test_common_items() {
+		let runner = Day03::parse_input(EXAMPLE);
+		assert_eq!(runner.common_items(3), Ok(vec!['r', 'Z']));
+
+		let runner = Day03::parse_input("abcd\nxbyd\nbzzz\nbwww");
+		assert_eq!(
+			runner.common_items(2),
+			Err("Group 1 has more than one item in common: \"bd\"".to_owned())
+		);
+		assert_eq!(runner.common_items(4), Ok(vec!['b']));
+		assert_eq!(
+			runner.common_items(3),
+			Err("Group 2 only has 1 of 3 elves".to_owned())
+		);
+		assert!(runner.common_items(0).is_err());
+
+		let runner = Day03::parse_input("aa\nbb");
+		assert_eq!(runner.common_items(1), Ok(vec!['a', 'b']));
+		assert_eq!(
+			runner.common_items(2),
+			Err("Group 1 has nothing in common".to_owned())
+		);
+	}
+
+	#[test]
+	#[should_panic(expected = "Line 2 has '1'")]
+	fn test_not_an_item() {
+		Day03::parse_input("ab\na1").part_one();
+	}
+
+	#[test]
+	fn test_audit() {
+		let runner = Day03::parse_input("abcabc\nabxbaz\na1é\nxyz");
+		let findings = runner.audit();
+
+		assert_eq!(
+			findings[1],
+			Finding {
+				line: 2,
+				misplaced: vec!['a', 'b'],
+				odd: false,
+				strays: vec![],
+			}
+		);
+		assert_eq!(findings[0].misplaced, vec!['a', 'b', 'c']);
+		assert_eq!(findings[2].strays, vec!['1', 'é']);
+		assert!(findings[2].odd && findings[3].odd);
+		assert!(findings[3].misplaced.is_empty());
+		assert_eq!(
+			runner.common_items(2),
+			Err("Line 3 has '1', which isn't an item".to_owned())
+		);
+
+		let totals = priority_totals(&findings);
+		assert_eq!(totals, vec![('a', 2, 2), ('b', 2, 4), ('c', 1, 3)]);
+	}
+
+	#[test]
+	fn test_priority_value() {
+		assert_eq!(priority_value('a'), Some(1));
+		assert_eq!(priority_value('z'), Some(26));
+		assert_eq!(priority_value('A'), Some(27));
+		assert_eq!(priority_value('Z'), Some(52));
+		assert_eq!(priority_value('?'), None);
 	}
 }
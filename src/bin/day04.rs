@@ -11,102 +11,523 @@
 //! Part 2
 //! ------
 //! Find out how many pairs contain any sort of overlap.
+//!
+//! Coverage
+//! --------
+//! Pairs are one thing, but the whole crew is another.
+//! `cargo run --bin day04 -- coverage 2 < input.txt` looks at every
+//! assignment at once, and finds the sections nobody's cleaning, the
+//! sections more than 2 elves are cleaning, and the elves who could stay
+//! home without a single section going uncleaned.
 #![warn(clippy::all)]
-use std::cmp::Ordering;
-use std::io::stdin;
+use std::env;
+use std::process;
 
-fn main() {
-	let lines = stdin().lines();
-	// Aaaaaaaaand guess who forgot to make the counter variable mutable? Oops.
-	let mut contains_count = 0;
-	let mut overlap_count = 0;
-	for line in lines {
-		if let Ok(pair) = line {
-			// `this` should be the first elf. `that` is the second elf.
-			let (this, that) = if pair.find(',').is_some() {
-				let line = pair.split(',').collect::<Vec<_>>();
-				(line[0], line[1])
-			} else {
-				panic!("Cleanup crew to aisle 7!")
+use advent::{input_to_str, Advent, Runner};
+
+#[derive(Debug)]
+struct Day04 {
+	/// Each pair of elves, and what they've been told to clean up.
+	pairs: Vec<(SectionRange, SectionRange)>,
+}
+
+/// The sections an elf has been assigned, from `start` to `end` inclusive.
+/// There's always at least one section in there.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct SectionRange {
+	start: u32,
+	end: u32,
+}
+
+impl SectionRange {
+	/// Make a range, as long as it doesn't go backwards.
+	fn new(start: u32, end: u32) -> Option<Self> {
+		(start <= end).then_some(SectionRange { start, end })
+	}
+
+	/// Read a range like `2-4`.
+	fn parse(range: &str) -> Result<Self, String> {
+		// Elf is slacking off!
+		let (start, end) = range
+			.split_once('-')
+			.ok_or_else(|| format!("{:?} isn't a range", range))?;
+		let parse = |section: &str| {
+			section.parse().map_err(|err| {
+				format!("What are you elves doing with {:?}? {}", section, err)
+			})
+		};
+		let (start, end) = (parse(start)?, parse(end)?);
+		Self::new(start, end)
+			.ok_or_else(|| format!("{:?} goes backwards", range))
+	}
+
+	/// Whether every section in `other` is also in this range.
+	fn contains(&self, other: &SectionRange) -> bool {
+		self.start <= other.start && other.end <= self.end
+	}
+
+	/// Whether there's any section in both ranges.
+	///
+	/// I stole a clever one-liner for this from elsewhere, and was never
+	/// _entirely_ sold on it. Asking whether there's anything in common is
+	/// a lot harder to get wrong.
+	fn overlaps(&self, other: &SectionRange) -> bool {
+		self.intersection(other).is_some()
+	}
+
+	/// The sections in both ranges, if there are any.
+	fn intersection(&self, other: &SectionRange) -> Option<SectionRange> {
+		Self::new(self.start.max(other.start), self.end.min(other.end))
+	}
+
+	/// How many sections are in the range.
+	fn len(&self) -> u32 {
+		self.end - self.start + 1
+	}
+}
+
+/// How many elves are cleaning each stretch of sections.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Coverage {
+	sections: SectionRange,
+	elves: usize,
+}
+
+impl Day04 {
+	/// Every elf's assignment, in input order. Elf `i` is in pair `i / 2`.
+	fn elves(&self) -> impl Iterator<Item = &SectionRange> {
+		self.pairs.iter().flat_map(|(this, that)| [this, that])
+	}
+
+	/// Split everything from the first assigned section to the last into
+	/// stretches where the same number of elves are cleaning, in order.
+	fn coverage(&self) -> Vec<Coverage> {
+		// Each elf starts cleaning at `start` and stops right after `end`.
+		// Sections could go all the way to `u32::MAX`, so work in u64.
+		let mut changes = self
+			.elves()
+			.flat_map(|elf| [(elf.start as u64, 1), (elf.end as u64 + 1, -1)])
+			.collect::<Vec<(u64, i64)>>();
+		changes.sort_unstable();
+
+		let mut stretches = Vec::new();
+		let mut elves = 0;
+		for (i, &(section, change)) in changes.iter().enumerate() {
+			elves += change;
+			let Some(&(next, _)) = changes.get(i + 1) else {
+				break;
 			};
-			let this = if this.find('-').is_some() {
-				let items = this.split('-').collect::<Vec<_>>();
-				let (upper, lower) = (items[0], items[1]);
-				let upper = upper.parse();
-				let lower = lower.parse();
-				match (upper, lower) {
-					(Ok(upper), Ok(lower)) => (upper, lower),
-					_ => panic!("What are you elves doing?"),
+			if next > section {
+				stretches.push(Coverage {
+					sections: SectionRange {
+						start: section as u32,
+						end: (next - 1) as u32,
+					},
+					elves: elves as usize,
+				});
+			}
+		}
+		stretches
+	}
+
+	/// Join up the stretches where `wanted` says yes.
+	fn stretches<F>(&self, wanted: F) -> Vec<SectionRange>
+	where
+		F: Fn(&Coverage) -> bool,
+	{
+		let mut found: Vec<SectionRange> = Vec::new();
+		for stretch in self.coverage().iter().filter(|c| wanted(c)) {
+			match found.last_mut() {
+				Some(last) if last.end + 1 == stretch.sections.start => {
+					last.end = stretch.sections.end;
 				}
-			} else {
-				panic!("Elf is slacking off!")
-			};
-			let that = if that.find('-').is_some() {
-				let items = that.split('-').collect::<Vec<_>>();
-				let (upper, lower) = (items[0], items[1]);
-				let upper = upper.parse();
-				let lower = lower.parse();
-				match (upper, lower) {
-					(Ok(upper), Ok(lower)) => (upper, lower),
-					_ => panic!("What are you elves doing?"),
+				_ => found.push(stretch.sections),
+			}
+		}
+		found
+	}
+
+	/// The gaps between assignments, where nobody is cleaning.
+	fn uncovered(&self) -> Vec<SectionRange> {
+		self.stretches(|c| c.elves == 0)
+	}
+
+	/// The sections more than `limit` elves are cleaning.
+	fn crowded(&self, limit: usize) -> Vec<SectionRange> {
+		self.stretches(|c| c.elves > limit)
+	}
+
+	/// The biggest bunch of elves that could all stay home without any
+	/// section going uncleaned, as indices into [`Day04::elves`].
+	///
+	/// This picks the fewest elves that still cover everything, and sends
+	/// the rest home. Going left to right, always keep the elf that reaches
+	/// furthest among the ones that start in time. Nobody could do better,
+	/// since any other elf we could keep would stop sooner.
+	fn redundant(&self) -> Vec<usize> {
+		let elves = self.elves().collect::<Vec<_>>();
+		let mut order = (0..elves.len()).collect::<Vec<_>>();
+		order.sort_by_key(|&i| elves[i].start);
+
+		let mut keep = vec![false; elves.len()];
+		// The first section nobody we're keeping is cleaning yet.
+		let mut next = 0;
+		let mut i = 0;
+		while i < order.len() {
+			// If there's a gap, jump over it.
+			next = next.max(elves[order[i]].start as u64);
+			let mut best: Option<usize> = None;
+			while i < order.len() && elves[order[i]].start as u64 <= next {
+				if best.is_none_or(|b| elves[order[i]].end > elves[b].end) {
+					best = Some(order[i]);
 				}
-			} else {
-				panic!("Elf is slacking off!")
-			};
-			if contains(this, that) {
-				contains_count += 1;
+				i += 1;
 			}
-			if overlaps(this, that) {
-				overlap_count += 1;
+			if let Some(best) = best {
+				let end = elves[best].end as u64 + 1;
+				if end > next {
+					keep[best] = true;
+					next = end;
+				}
 			}
-		} else {
-			panic!("Input has failed! AAAAAAAAAH!");
 		}
+		(0..elves.len()).filter(|&i| !keep[i]).collect()
+	}
+}
+
+impl Advent for Day04 {
+	type Answer1 = usize;
+
+	type Answer2 = usize;
+
+	fn parse_input(input: &str) -> Self {
+		let pairs = input
+			.lines()
+			.enumerate()
+			.map(|(i, line)| {
+				// `this` should be the first elf. `that` is the second elf.
+				let (this, that) = line.split_once(',').unwrap_or_else(|| {
+					panic!("Cleanup crew to aisle {}!", i + 1)
+				});
+				let parse = |range| {
+					SectionRange::parse(range)
+						.unwrap_or_else(|err| panic!("Line {}: {}", i + 1, err))
+				};
+				(parse(this), parse(that))
+			})
+			.collect();
+		Day04 { pairs }
+	}
+
+	fn part_one(&self) -> usize {
+		self.pairs
+			.iter()
+			.filter(|(this, that)| this.contains(that) || that.contains(this))
+			.count()
+	}
+
+	fn part_two(&self) -> usize {
+		self.pairs
+			.iter()
+			.filter(|(this, that)| this.overlaps(that))
+			.count()
 	}
+}
+
+fn main() {
+	let args = env::args().skip(1).collect::<Vec<_>>();
+	if args.first().map(String::as_str) == Some("coverage") {
+		let limit = match args.get(1).map(|arg| arg.parse()) {
+			None => 2,
+			Some(Ok(limit)) if args.len() == 2 => limit,
+			_ => {
+				eprintln!("Usage: coverage [crowd size]");
+				process::exit(2);
+			}
+		};
+		coverage(limit);
+		return;
+	}
+	let mut runner = Runner::<Day04>::from_stdin();
 	println!(
 		"The number of poorly planned elf pairs is {}",
-		contains_count
+		runner.part_one()
+	);
+	println!(
+		"The number of unneeded collaborations is {}",
+		runner.part_two()
 	);
-	println!("The number of unneeded collaborations is {}", overlap_count);
+	runner.report();
 }
 
-fn contains(this: (i32, i32), that: (i32, i32)) -> bool {
-	match this.0.cmp(&that.0) {
-		Ordering::Less => {
-			// The start of the first range is before the start
-			// of the second range.
-			// The end of the first range is _after_ the start
-			// of the second range, so it counts.
-			this.1 >= that.1
-		}
-		Ordering::Equal => {
-			// The start of this range is exactly equal
-			// the start of the second range. Therefore, one elf
-			// containing another should be guaranteed?
-			true
+/// Handle `coverage`, by printing what the whole crew is up to.
+fn coverage(limit: usize) {
+	let puzzle = Day04::parse_input(&input_to_str());
+	let elves = puzzle.elves().collect::<Vec<_>>();
+	let (Some(first), Some(last)) = (
+		elves.iter().map(|elf| elf.start).min(),
+		elves.iter().map(|elf| elf.end).max(),
+	) else {
+		println!("Nobody's cleaning anything.");
+		return;
+	};
+	let list = |ranges: Vec<SectionRange>| {
+		let total = ranges.iter().map(|r| r.len() as u64).sum::<u64>();
+		let ranges = ranges
+			.iter()
+			.map(|r| format!("{}-{}", r.start, r.end))
+			.collect::<Vec<_>>();
+		format!("{} ({} sections)", ranges.join(", "), total)
+	};
+	println!(
+		"{} elves are cleaning sections {} to {}.",
+		elves.len(),
+		first,
+		last
+	);
+
+	let uncovered = puzzle.uncovered();
+	if uncovered.is_empty() {
+		println!("Every section in between is getting cleaned.");
+	} else {
+		println!("Nobody is cleaning {}.", list(uncovered));
+	}
+
+	let crowded = puzzle.crowded(limit);
+	if crowded.is_empty() {
+		println!("No section has more than {} elves on it.", limit);
+	} else {
+		println!("More than {} elves are cleaning {}.", limit, list(crowded));
+	}
+
+	let redundant = puzzle.redundant();
+	println!(
+		"{} of them could stay home and nobody would notice:",
+		redundant.len()
+	);
+	for i in redundant {
+		let elf = elves[i];
+		println!(
+			"  pair {}, elf {} ({}-{})",
+			i / 2 + 1,
+			i % 2 + 1,
+			elf.start,
+			elf.end
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use advent::rng::Rng;
+
+	const EXAMPLE: &str =
+		"2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8";
+
+	/// Every range that fits in `0..=6`, which is enough room for two ranges
+	/// to be in every position relative to each other.
+	fn small_ranges() -> Vec<SectionRange> {
+		(0..=6)
+			.flat_map(|start| (start..=6).map(move |end| (start, end)))
+			.map(|(start, end)| SectionRange::new(start, end).unwrap())
+			.collect()
+	}
+
+	/// The sections in a range, the slow and obvious way.
+	fn sections(range: &SectionRange) -> Vec<u32> {
+		(range.start..=range.end).collect()
+	}
+
+	#[test]
+	fn test_parts() {
+		let runner = Day04::parse_input(EXAMPLE);
+		assert_eq!(runner.part_one(), 2);
+		assert_eq!(runner.part_two(), 4);
+	}
+
+	#[test]
+	fn test_parse() {
+		assert_eq!(
+			SectionRange::parse("2-4"),
+			Ok(SectionRange { start: 2, end: 4 })
+		);
+		assert_eq!(
+			SectionRange::parse("6-6"),
+			Ok(SectionRange { start: 6, end: 6 })
+		);
+		assert!(SectionRange::parse("4-2").is_err());
+		assert!(SectionRange::parse("4").is_err());
+		assert!(SectionRange::parse("a-2").is_err());
+		assert!(SectionRange::parse("-1-2").is_err());
+	}
+
+	#[test]
+	#[should_panic(expected = "Line 2")]
+	fn test_bad_input() {
+		Day04::parse_input("1-2,3-4\n1-2,4-3");
+	}
+
+	#[test]
+	fn test_coverage() {
+		let runner = Day04::parse_input(EXAMPLE);
+		let coverage = runner
+			.coverage()
+			.iter()
+			.map(|c| (c.sections.start, c.sections.end, c.elves))
+			.collect::<Vec<_>>();
+		let expected = vec![
+			(2, 2, 4),
+			(3, 3, 5),
+			(4, 4, 7),
+			(5, 5, 7),
+			(6, 6, 8),
+			(7, 7, 6),
+			(8, 8, 4),
+			(9, 9, 1),
+		];
+		assert_eq!(coverage, expected);
+
+		assert_eq!(runner.uncovered(), vec![]);
+		assert_eq!(runner.crowded(6), vec![SectionRange { start: 4, end: 6 }]);
+		assert_eq!(runner.crowded(8), vec![]);
+		assert_eq!(runner.crowded(0), vec![SectionRange { start: 2, end: 9 }]);
+	}
+
+	#[test]
+	fn test_gaps() {
+		let runner = Day04::parse_input("1-2,5-6\n9-9,4294967295-4294967295");
+		assert_eq!(
+			runner.uncovered(),
+			vec![
+				SectionRange { start: 3, end: 4 },
+				SectionRange { start: 7, end: 8 },
+				SectionRange {
+					start: 10,
+					end: u32::MAX - 1
+				},
+			]
+		);
+		assert_eq!(runner.redundant(), vec![]);
+	}
+
+	#[test]
+	fn test_redundant() {
+		let runner = Day04::parse_input(EXAMPLE);
+		// 2-8 and 7-9 cover it all, so only elves 6 and 5 stay.
+		let redundant = runner.redundant();
+		assert_eq!(redundant, vec![0, 1, 2, 3, 4, 7, 8, 9, 10, 11]);
+
+		// Check some awkward crews against every possible group of elves
+		// staying home: duplicates, gaps, chains that cover something in
+		// the middle, and ranges that only just touch.
+		let crews = [
+			"1-3,2-4\n3-5,1-5",
+			"1-2,3-4\n5-6,2-5",
+			"1-4,3-6\n5-8,2-7",
+			"1-1,1-1\n1-1,2-2",
+			"1-3,5-7\n9-9,2-8",
+			"1-5,2-3\n4-9,6-7\n8-12,3-10",
+			"1-2,2-3\n3-4,4-5\n1-3,3-5",
+			"10-12,1-11\n4-6,11-12\n2-3,5-9",
+			"1-6,2-2\n2-5,3-8\n7-9,8-12\n6-10,11-11",
+		];
+		let mut runners = crews
+			.map(Day04::parse_input)
+			.into_iter()
+			.collect::<Vec<_>>();
+
+		// And a pile of little deterministic ones, from a little
+		// deterministic mess.
+		let mut rng = Rng::new(7);
+		for _ in 0..200 {
+			let pairs = (0..4)
+				.map(|_| {
+					let mut range = || {
+						let start = rng.below(12);
+						SectionRange::new(start, start + rng.below(5)).unwrap()
+					};
+					(range(), range())
+				})
+				.collect();
+			runners.push(Day04 { pairs });
 		}
-		Ordering::Greater => {
-			// The start of this range is _inside_ the start of the
-			// other range. Ergo, we need to check if this range is
-			// smaller.
-			this.1 <= that.1
+		for runner in runners {
+			let elves = runner.elves().copied().collect::<Vec<_>>();
+			let cleaned = |staying: &mut dyn Iterator<Item = &SectionRange>| {
+				let mut sections = staying
+					.flat_map(|elf| elf.start..=elf.end)
+					.collect::<Vec<_>>();
+				sections.sort_unstable();
+				sections.dedup();
+				sections
+			};
+			let everything = cleaned(&mut elves.iter());
+
+			let redundant = runner.redundant();
+			let mut staying = elves
+				.iter()
+				.enumerate()
+				.filter(|(i, _)| !redundant.contains(i))
+				.map(|(_, elf)| elf);
+			assert_eq!(cleaned(&mut staying), everything, "{:?}", elves);
+
+			let most = (0..1u32 << elves.len())
+				.filter(|home| {
+					let mut staying = elves
+						.iter()
+						.enumerate()
+						.filter(|(i, _)| home & 1 << i == 0)
+						.map(|(_, elf)| elf);
+					cleaned(&mut staying) == everything
+				})
+				.map(u32::count_ones)
+				.max();
+			assert_eq!(Some(redundant.len() as u32), most, "{:?}", elves);
 		}
 	}
-}
 
-/// Determine whether one range overlaps another range.
-///
-/// This checks for the criteria for Part 2 of the puzzle.
-///
-/// I stole this solution from elsewhere, so I'm not _entirely_
-/// sold on the logic of it?
-fn overlaps(this: (i32, i32), that: (i32, i32)) -> bool {
-	// Check whether the start of `this` overlaps with the end of `that`.
-	if this.0 <= that.1 {
-		// Now reverse the check to make sure yes, we're dealing with
-		// the same numbers.
-		that.0 <= this.1
-	} else {
-		false
+	#[test]
+	fn test_against_sets() {
+		let ranges = small_ranges();
+		// 7 ranges of length 1, 6 of length 2, and so on.
+		assert_eq!(ranges.len(), 28);
+		for this in &ranges {
+			let these = sections(this);
+			assert_eq!(this.len() as usize, these.len());
+			for that in &ranges {
+				let those = sections(that);
+				let shared = these
+					.iter()
+					.copied()
+					.filter(|s| those.contains(s))
+					.collect::<Vec<_>>();
+
+				let context = format!("{:?} and {:?}", this, that);
+				assert_eq!(
+					this.contains(that),
+					those.iter().all(|s| these.contains(s)),
+					"{}",
+					context
+				);
+				assert_eq!(
+					this.overlaps(that),
+					!shared.is_empty(),
+					"{}",
+					context
+				);
+				assert_eq!(
+					this.intersection(that)
+						.map(|r| sections(&r))
+						.unwrap_or_default(),
+					shared,
+					"{}",
+					context
+				);
+				// These shouldn't care about the order.
+				assert_eq!(this.overlaps(that), that.overlaps(this));
+				assert_eq!(this.intersection(that), that.intersection(this));
+			}
+		}
 	}
 }
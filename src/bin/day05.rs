@@ -18,22 +18,52 @@
 //! ------
 //! Turns out crates are moved in a first in, FIRST out fashion. Oops.
 //! Read the top crate of each stack.
+//!
+//! Other cranes
+//! ------------
+//! The difference between the parts is just the [`Crane`], and there are
+//! more models in the catalogue. Try one out with
+//! `cargo run --bin day05 -- crane limited:2 < input.txt`. The models are
+//! `9000`, `9001`, `limited:<capacity>` and `bottom`.
+//!
+//! To watch it happen, use `trace` instead of `crane`, which draws the
+//! stacks after every move. Either way, an instruction that can't be done
+//! gets called out by number instead of taking the whole thing down.
+//!
+//! Planning
+//! --------
+//! And for the warehouse-planning crowd, the question in reverse: given the
+//! sketch and the tops you want, what's the shortest list of instructions
+//! that gets there? `cargo run --bin day05 -- plan 9000 CMZ < input.txt`
+//! finds out, as long as it takes at most 6 moves. Add a number at the end
+//! to look further, but the search gets big fast.
 
+use std::collections::{HashMap, VecDeque};
+use std::env;
 use std::fmt::{self, Display, Formatter};
-use std::io::stdin;
+use std::process;
+use std::str::FromStr;
 
-/// This enum is copied from elsewhere so that I can
-/// use my standard `stdin().lines()` method.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum ParserState {
-	Stacks,
-	Noise,
-	Instructions,
+use advent::{input_to_str, Advent, Runner};
+
+#[derive(Debug)]
+struct Day05 {
+	stacks: Stacks,
+	tasks: Vec<Task>,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+/// The stacks of crates, bottom first, as in the sketch at the top of the
+/// input. Parse one with [`str::parse`], and [`Display`] draws it right back
+/// out again.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Stacks(Vec<Vec<char>>);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 /// An instruction in the input. Could this be a tuple? Yeah, but I want to
 /// be able to read this on Christmas Day, so struct it is!
+///
+/// Stacks are numbered the way the input does it, starting from one. Since
+/// this isn't Lua, that needs a decrement before indexing.
 struct Task {
 	count: usize,
 	source: usize,
@@ -41,7 +71,7 @@ struct Task {
 }
 
 impl Display for Task {
-	/// This is technically unnecessary, but I thought it'd be handy.
+	/// Write the instruction the way the puzzle does.
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
 		f.write_fmt(format_args!(
 			"move {} from {} to {}",
@@ -50,116 +80,739 @@ impl Display for Task {
 	}
 }
 
-fn main() {
-	let lines = stdin().lines();
-	let mut stacks = Vec::new();
-	let mut state = ParserState::Stacks;
-	let mut tasks = Vec::new();
-	for line in lines {
-		if let Ok(line) = line {
-			match state {
-				ParserState::Stacks => {
-					if line.starts_with(" 1") {
-						// This is where the useful information about the stacks
-						// STOPS, and as such, we can skip it.
-						state = ParserState::Noise;
-						continue;
-					}
-					let chars = line.chars().collect::<Vec<_>>();
-					for (idx, val) in chars.chunks(4).enumerate() {
-						if stacks.len() <= idx {
-							stacks.push(vec![]);
-						}
-						if val[1] != ' ' {
-							// And as such, matches `[?] `...
-							stacks[idx].push(val[1]);
+impl FromStr for Task {
+	type Err = String;
+
+	/// Right, time for the fun part. Line format is `move X from Y to Z`.
+	fn from_str(line: &str) -> Result<Self, String> {
+		let words: Vec<_> = line.split(' ').collect();
+		let [move_, count, from, source, to, dest] = words[..] else {
+			return Err(format!("{:?} isn't an instruction", line));
+		};
+		if (move_, from, to) != ("move", "from", "to") {
+			return Err(format!("{:?} isn't an instruction", line));
+		}
+		let number = |word: &str, complaint: &str| {
+			word.parse()
+				.map_err(|_| format!("{} ({:?})", complaint, word))
+		};
+		Ok(Task {
+			count: number(
+				count,
+				"There is no north of here, it's the North Pole!",
+			)?,
+			source: number(source, "This crane can't lift i crates, y'know.")?,
+			dest: number(dest, "Sure, just drop it off a cliff...")?,
+		})
+	}
+}
+
+/// Why an instruction couldn't be followed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Problem {
+	/// The instruction mentions a stack that isn't in the sketch.
+	NoSuchStack(usize),
+	/// There aren't enough crates on the source stack to move.
+	NotEnoughCrates { stack: usize, height: usize },
+}
+
+/// An instruction that couldn't be followed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct MoveError {
+	/// Which instruction it was, starting from zero.
+	index: usize,
+	/// The instruction itself.
+	task: Task,
+	/// What's wrong with it.
+	problem: Problem,
+}
+
+impl Display for MoveError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		write!(f, "Instruction {} ({}): ", self.index + 1, self.task)?;
+		match self.problem {
+			Problem::NoSuchStack(stack) => {
+				write!(f, "there's no stack {}", stack)
+			}
+			Problem::NotEnoughCrates { stack, height } => write!(
+				f,
+				"Can't stack dirt, Claus! Stack {} only has {} crates",
+				stack, height
+			),
+		}
+	}
+}
+
+/// Something that can move crates around.
+trait Crane {
+	/// Take `count` crates off `stack`, and return them in the order they
+	/// land on the destination stack. There are always at least `count`
+	/// crates to take.
+	fn pick(&self, stack: &mut Vec<char>, count: usize) -> Vec<char>;
+}
+
+/// Moves the crates one by one, first in, last out.
+struct CrateMover9000;
+
+/// Moves all of the crates at once, so they keep their order.
+struct CrateMover9001;
+
+/// A CrateMover 9001 that can only lift so many crates at once. Bigger
+/// moves get split up, top crates first.
+struct LimitedCrane {
+	capacity: usize,
+}
+
+/// Pulls crates out from the bottom of the stack, one by one. Don't ask how.
+struct BottomCrane;
+
+impl Crane for CrateMover9000 {
+	fn pick(&self, stack: &mut Vec<char>, count: usize) -> Vec<char> {
+		let mut crates = stack.split_off(stack.len() - count);
+		crates.reverse();
+		crates
+	}
+}
+
+impl Crane for CrateMover9001 {
+	fn pick(&self, stack: &mut Vec<char>, count: usize) -> Vec<char> {
+		stack.split_off(stack.len() - count)
+	}
+}
+
+impl Crane for LimitedCrane {
+	fn pick(&self, stack: &mut Vec<char>, count: usize) -> Vec<char> {
+		let crates = stack.split_off(stack.len() - count);
+		// The top load goes first, and everything after lands on top of it.
+		crates
+			.rchunks(self.capacity)
+			.flat_map(|load| load.iter().copied())
+			.collect()
+	}
+}
+
+impl Crane for BottomCrane {
+	fn pick(&self, stack: &mut Vec<char>, count: usize) -> Vec<char> {
+		stack.drain(..count).collect()
+	}
+}
+
+/// Look up a crane from the catalogue by its model name.
+fn crane_model(name: &str) -> Result<Box<dyn Crane>, String> {
+	match name.split_once(':') {
+		None if name == "9000" => Ok(Box::new(CrateMover9000)),
+		None if name == "9001" => Ok(Box::new(CrateMover9001)),
+		None if name == "bottom" => Ok(Box::new(BottomCrane)),
+		Some(("limited", capacity)) => match capacity.parse() {
+			Ok(capacity) if capacity > 0 => {
+				Ok(Box::new(LimitedCrane { capacity }))
+			}
+			_ => Err(format!("{:?} isn't a capacity", capacity)),
+		},
+		_ => Err(format!("There's no {:?} crane", name)),
+	}
+}
+
+impl Stacks {
+	/// Carry out one instruction with the given crane, if it can be done.
+	fn apply<C>(&mut self, crane: &C, task: &Task) -> Result<(), Problem>
+	where
+		C: Crane + ?Sized,
+	{
+		let count = self.0.len();
+		for stack in [task.source, task.dest] {
+			if !(1..=count).contains(&stack) {
+				return Err(Problem::NoSuchStack(stack));
+			}
+		}
+		let source = &mut self.0[task.source - 1];
+		if source.len() < task.count {
+			return Err(Problem::NotEnoughCrates {
+				stack: task.source,
+				height: source.len(),
+			});
+		}
+		let mut crates = crane.pick(source, task.count);
+		self.0[task.dest - 1].append(&mut crates);
+		Ok(())
+	}
+
+	/// Find the shortest list of instructions that leaves `target` on top,
+	/// using at most `max_moves` of them. If it can't be done in that many,
+	/// there's no plan.
+	///
+	/// This tries every move from every arrangement it's seen, closest
+	/// first, so it gets slow fast. Keep `max_moves` small.
+	fn plan<C>(
+		&self,
+		crane: &C,
+		target: &str,
+		max_moves: usize,
+	) -> Option<Vec<Task>>
+	where
+		C: Crane + ?Sized,
+	{
+		// Where each arrangement came from, and how we got it from there.
+		let mut seen: HashMap<Stacks, Option<(Stacks, Task)>> = HashMap::new();
+		seen.insert(self.clone(), None);
+		let mut queue = VecDeque::from([(self.clone(), 0)]);
+		while let Some((stacks, moves)) = queue.pop_front() {
+			if stacks.tops() == target {
+				// Walk back to the start to get the plan.
+				let mut plan = Vec::new();
+				let mut current = &stacks;
+				while let Some(Some((previous, task))) = seen.get(current) {
+					plan.push(*task);
+					current = previous;
+				}
+				plan.reverse();
+				return Some(plan);
+			}
+			if moves == max_moves {
+				continue;
+			}
+			let count = stacks.0.len();
+			for source in 1..=count {
+				for dest in (1..=count).filter(|&dest| dest != source) {
+					for crates in 1..=stacks.0[source - 1].len() {
+						let task = Task {
+							count: crates,
+							source,
+							dest,
+						};
+						let mut next = stacks.clone();
+						// The loops only pick moves that fit.
+						next.apply(crane, &task)
+							.expect("Every move here has enough crates");
+						if seen.contains_key(&next) {
+							continue;
 						}
+						seen.insert(next.clone(), Some((stacks.clone(), task)));
+						queue.push_back((next, moves + 1));
 					}
 				}
-				ParserState::Noise => {
-					// This should just be an empty line, so we can skip it.
-					state = ParserState::Instructions;
-					// What we DO need to do is reverse all of the stacks.
-					for stack in stacks.iter_mut() {
-						stack.reverse();
+			}
+		}
+		None
+	}
+
+	/// Turn the stacks into a results string, as expected by Advent of Code.
+	/// Iterators are fun.
+	fn tops(&self) -> String {
+		self.0
+			.iter()
+			// Get just the last element from each stack. This ignores any
+			// empty stacks, so...
+			.filter_map(|s| s.last())
+			.collect()
+	}
+}
+
+impl FromStr for Stacks {
+	type Err = String;
+
+	/// Read a sketch like this, where the last line numbers the stacks:
+	/// ```text
+	///     [D]
+	/// [N] [C]
+	/// [Z] [M] [P]
+	///  1   2   3
+	/// ```
+	/// Trailing spaces are optional, since editors like to eat them.
+	fn from_str(sketch: &str) -> Result<Self, String> {
+		let mut lines = sketch.lines().collect::<Vec<_>>();
+		// This is where the useful information about the stacks STOPS.
+		let footer = lines.pop().ok_or("There's no sketch")?;
+		let count = footer.split_whitespace().count();
+		let numbered = footer
+			.split_whitespace()
+			.zip(1..)
+			.all(|(label, number)| label == number.to_string());
+		if count == 0 || !numbered {
+			return Err(format!("{:?} doesn't number the stacks", footer));
+		}
+
+		let mut stacks = vec![Vec::new(); count];
+		// Crates get stacked from the bottom up, so read upwards.
+		for (i, line) in lines.iter().enumerate().rev() {
+			let chars = line.chars().collect::<Vec<_>>();
+			for (idx, cell) in chars.chunks(4).enumerate() {
+				let cell = cell.iter().collect::<String>();
+				let label = match cell.trim_end() {
+					"" => continue,
+					// And as such, matches `[?] `...
+					cell if cell.len() == 3
+						&& cell.starts_with('[')
+						&& cell.ends_with(']') =>
+					{
+						cell.chars().nth(1).unwrap_or(' ')
 					}
-					continue;
-				}
-				ParserState::Instructions => {
-					// Right, time for the fun part.
-					// Line format is `move X from Y to Z`
-					let words: Vec<_> = line.split(' ').collect();
-					// This is after `move` in the input.
-					let count = words[1].parse().expect(
-						"There is no north of here, it's the North Pole!",
-					);
-					// This is after `from` in the input.
-					let start: usize = words[3]
-						.parse()
-						.expect("This crane can't lift i crates, y'know.");
-					// This is after `to` in the input.
-					let stop: usize = words[5]
-						.parse()
-						.expect("Sure, just drop it off a cliff...");
-					tasks.push(Task {
-						count,
-						// Since this isn't Lua, we need to decrement here.
-						source: start - 1,
-						// And here.
-						dest: stop - 1,
-					});
+					_ => {
+						return Err(format!(
+							"Line {}: {:?} isn't a crate",
+							i + 1,
+							cell
+						))
+					}
+				};
+				let stack = stacks.get_mut(idx).ok_or_else(|| {
+					format!("Line {}: there's no stack {}", i + 1, idx + 1)
+				})?;
+				// Everything below this crate should already be stacked.
+				if stack.len() != lines.len() - 1 - i {
+					return Err(format!(
+						"Line {}: crate {} is floating",
+						i + 1,
+						label
+					));
 				}
+				stack.push(label);
+			}
+		}
+		Ok(Stacks(stacks))
+	}
+}
+
+impl Display for Stacks {
+	/// Draw the stacks the same way the puzzle does, footer and all.
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		let height = self.0.iter().map(Vec::len).max().unwrap_or(0);
+		for row in (0..height).rev() {
+			let cells = self
+				.0
+				.iter()
+				.map(|stack| match stack.get(row) {
+					Some(label) => format!("[{}]", label),
+					None => "   ".to_owned(),
+				})
+				.collect::<Vec<_>>();
+			writeln!(f, "{}", cells.join(" "))?;
+		}
+		let footer = (1..=self.0.len())
+			.map(|number| format!("{:^3}", number))
+			.collect::<Vec<_>>();
+		write!(f, "{}", footer.join(" "))
+	}
+}
+
+impl Advent for Day05 {
+	type Answer1 = String;
+
+	type Answer2 = String;
+
+	fn parse_input(input: &str) -> Self {
+		// The sketch and the instructions are separated by an empty line.
+		let (sketch, instructions) = input
+			.split_once("\n\n")
+			.expect("Where did the instructions go?");
+		let stacks = sketch
+			.parse()
+			.unwrap_or_else(|err| panic!("Bad sketch: {}", err));
+		let tasks = instructions
+			.lines()
+			.enumerate()
+			.map(|(i, line)| {
+				line.parse().unwrap_or_else(|err| {
+					panic!("Instruction {}: {}", i + 1, err)
+				})
+			})
+			.collect();
+		Day05 { stacks, tasks }
+	}
+
+	/// This moves all of the boxes one by one.
+	fn part_one(&self) -> String {
+		match self.simulate(&CrateMover9000) {
+			Ok(stacks) => stacks.tops(),
+			Err(err) => panic!("{}", err),
+		}
+	}
+
+	/// This moves all of the boxes at once.
+	fn part_two(&self) -> String {
+		match self.simulate(&CrateMover9001) {
+			Ok(stacks) => stacks.tops(),
+			Err(err) => panic!("{}", err),
+		}
+	}
+}
+
+impl Day05 {
+	/// Follow every instruction with the given crane, and see where the
+	/// crates end up.
+	fn simulate<C>(&self, crane: &C) -> Result<Stacks, MoveError>
+	where
+		C: Crane + ?Sized,
+	{
+		self.trace(crane, |_, _| {})
+	}
+
+	/// Like [`Day05::simulate`], but `after` gets a look at the stacks after
+	/// every move, along with the move that was just made.
+	///
+	/// Every instruction is checked before it's carried out, so if one can't
+	/// be done, this stops there and says which one.
+	fn trace<C, F>(&self, crane: &C, mut after: F) -> Result<Stacks, MoveError>
+	where
+		C: Crane + ?Sized,
+		F: FnMut(&Task, &Stacks),
+	{
+		let mut stacks = self.stacks.clone();
+		for (index, task) in self.tasks.iter().enumerate() {
+			let fail = |problem| MoveError {
+				index,
+				task: *task,
+				problem,
+			};
+			stacks.apply(crane, task).map_err(fail)?;
+			after(task, &stacks);
+		}
+		Ok(stacks)
+	}
+}
+
+fn main() {
+	let args = env::args().skip(1).collect::<Vec<_>>();
+	if let Some(command @ ("crane" | "trace")) =
+		args.first().map(String::as_str)
+	{
+		let crane = match args.get(1).map(|name| crane_model(name)) {
+			Some(Ok(crane)) if args.len() == 2 => crane,
+			Some(Err(err)) => {
+				eprintln!("{}", err);
+				process::exit(2);
+			}
+			_ => {
+				eprintln!(
+					"Usage: {} <9000|9001|limited:<capacity>|bottom>",
+					command
+				);
+				process::exit(2);
+			}
+		};
+		run_crane(crane.as_ref(), command == "trace");
+		return;
+	}
+	if args.first().map(String::as_str) == Some("plan") {
+		let max_moves = match args.get(3).map(|arg| arg.parse()) {
+			None => Some(6),
+			Some(max_moves) => max_moves.ok(),
+		};
+		let crane = args.get(1).map(|name| crane_model(name));
+		match (crane, args.get(2), max_moves) {
+			(Some(Ok(crane)), Some(target), Some(max_moves))
+				if args.len() <= 4 =>
+			{
+				plan(crane.as_ref(), target, max_moves)
+			}
+			(Some(Err(err)), _, _) => {
+				eprintln!("{}", err);
+				process::exit(2);
+			}
+			_ => {
+				eprintln!("Usage: plan <crane> <tops> [max moves]");
+				process::exit(2);
 			}
-		} else {
-			panic!("Input has failed! AAAAAAAAAAH!")
 		}
+		return;
 	}
-	let result = get_result(part_one(&stacks, tasks.clone()).iter());
+	let mut runner = Runner::<Day05>::from_stdin();
 	// Print the end result.
-	println!("End result from above looks like {}", result);
-	let result = get_result(part_two(&stacks, tasks).iter());
-	println!("End result done properly looks like {} from above", result);
-}
-
-/// Turn the thing of stacks into a results string, as expected
-/// by Advent of Code. Iterators are fun.
-fn get_result<'a, I>(iter: I) -> String
-where
-	I: Iterator<Item = &'a Vec<char>>,
-{
-	iter
-		// Get just the last element from each stack. This ignores any empty
-		// stacks, so...
-		.filter_map(|s| s.last())
-		// Convert the nasty type into something I can actually USE.
-		.collect::<String>()
-}
-
-/// This function moves all of the boxes one by one.
-fn part_one(stacks: &[Vec<char>], tasks: Vec<Task>) -> Vec<Vec<char>> {
-	let mut stacks = stacks.to_owned();
-	for task in tasks {
-		for _ in 0..task.count {
-			let item =
-				stacks[task.source].pop().expect("Can't stack dirt, Claus!");
-			stacks[task.dest].push(item);
-		}
-	}
-	stacks
-}
-
-/// This function moves all of the boxes at once.
-fn part_two(stacks: &[Vec<char>], tasks: Vec<Task>) -> Vec<Vec<char>> {
-	let mut stacks = stacks.to_owned();
-	for task in tasks {
-		let mut crane = Vec::new();
-		for _ in 0..task.count {
-			let item =
-				stacks[task.source].pop().expect("Can't stack dirt, Claus!");
-			crane.push(item);
-		}
-		crane.reverse();
-		stacks[task.dest].append(&mut crane);
-	}
-	stacks
+	println!("End result from above looks like {}", runner.part_one());
+	println!(
+		"End result done properly looks like {} from above",
+		runner.part_two()
+	);
+	runner.report();
+}
+
+/// Handle `plan`, by working out how to get `target` on top. The input only
+/// needs the sketch, but any instructions after it are fine too.
+fn plan(crane: &dyn Crane, target: &str, max_moves: usize) {
+	let input = input_to_str();
+	let sketch = input.split("\n\n").next().unwrap_or_default();
+	let stacks = sketch.parse::<Stacks>().unwrap_or_else(|err| {
+		eprintln!("Bad sketch: {}", err);
+		process::exit(1);
+	});
+	match stacks.plan(crane, target, max_moves) {
+		Some(plan) => {
+			for task in plan {
+				println!("{}", task);
+			}
+		}
+		None => {
+			eprintln!("Can't get {} on top in {} moves", target, max_moves);
+			process::exit(1);
+		}
+	}
+}
+
+/// Handle `crane` and `trace`, by running the instructions with a crane
+/// from the catalogue.
+fn run_crane(crane: &dyn Crane, trace: bool) {
+	let puzzle = Day05::parse_input(&input_to_str());
+	let mut step = 0;
+	let result = puzzle.trace(crane, |task, stacks| {
+		if trace {
+			step += 1;
+			println!("{}: {}", step, task);
+			println!("{}\n", stacks);
+		}
+	});
+	match result {
+		Ok(stacks) => {
+			if !trace {
+				println!("{}", stacks);
+			}
+			println!("End result looks like {} from above", stacks.tops());
+		}
+		Err(err) => {
+			eprintln!("{}", err);
+			process::exit(1);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use advent::rng::Rng;
+
+	const SKETCH: &str = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ";
+
+	const EXAMPLE: &str =
+		"    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\n\
+		move 1 from 2 to 1\n\
+		move 3 from 1 to 3\n\
+		move 2 from 2 to 1\n\
+		move 1 from 1 to 2";
+
+	#[test]
+	fn test_parts() {
+		let runner = Day05::parse_input(EXAMPLE);
+		assert_eq!(runner.part_one(), "CMZ");
+		assert_eq!(runner.part_two(), "MCD");
+	}
+
+	#[test]
+	fn test_cranes() {
+		let pick = |crane: &dyn Crane, count| {
+			let mut stack = vec!['A', 'B', 'C', 'D', 'E'];
+			let crates = crane.pick(&mut stack, count);
+			(
+				stack.into_iter().collect::<String>(),
+				crates.into_iter().collect::<String>(),
+			)
+		};
+		let owned = |(a, b): (&str, &str)| (a.to_owned(), b.to_owned());
+		assert_eq!(pick(&CrateMover9000, 3), owned(("AB", "EDC")));
+		assert_eq!(pick(&CrateMover9001, 3), owned(("AB", "CDE")));
+		assert_eq!(
+			pick(&LimitedCrane { capacity: 2 }, 5),
+			owned(("", "DEBCA"))
+		);
+		assert_eq!(
+			pick(&LimitedCrane { capacity: 1 }, 3),
+			pick(&CrateMover9000, 3)
+		);
+		assert_eq!(
+			pick(&LimitedCrane { capacity: 9 }, 3),
+			pick(&CrateMover9001, 3)
+		);
+		assert_eq!(pick(&BottomCrane, 2), owned(("CDE", "AB")));
+	}
+
+	#[test]
+	fn test_simulate() {
+		let runner = Day05::parse_input(EXAMPLE);
+		// 3 from 1 to 3 is Z, N and D, in two loads: N and D first, then Z.
+		let limited = crane_model("limited:2").unwrap();
+		assert_eq!(runner.simulate(limited.as_ref()).unwrap().tops(), "MCZ");
+		let limited = crane_model("limited:1").unwrap();
+		assert_eq!(runner.simulate(limited.as_ref()).unwrap().tops(), "CMZ");
+
+		let bottom =
+			"        [M]\n        [N]\n        [Z]\n[D] [C] [P]\n 1   2   3 ";
+		assert_eq!(runner.simulate(&BottomCrane).unwrap().to_string(), bottom);
+		assert!(crane_model("limited:0").is_err());
+		assert!(crane_model("9002").is_err());
+	}
+
+	#[test]
+	fn test_tasks() {
+		let task = "move 3 from 1 to 12".parse::<Task>();
+		let expected = Task {
+			count: 3,
+			source: 1,
+			dest: 12,
+		};
+		assert_eq!(task, Ok(expected));
+		assert_eq!(expected.to_string(), "move 3 from 1 to 12");
+
+		assert!("move 3 from 1".parse::<Task>().is_err());
+		assert!("move 3 to 1 from 2".parse::<Task>().is_err());
+		assert!("move -3 from 1 to 2".parse::<Task>().is_err());
+		assert!("move 3 from 1 to 2 please".parse::<Task>().is_err());
+	}
+
+	#[test]
+	fn test_validation() {
+		let mut runner = Day05::parse_input(EXAMPLE);
+		let check = |runner: &Day05, problem| {
+			let err = runner.simulate(&CrateMover9000).unwrap_err();
+			assert_eq!(err.index, 2);
+			assert_eq!(err.problem, problem);
+			err.to_string()
+		};
+
+		runner.tasks[2].count = 4;
+		let problem = Problem::NotEnoughCrates {
+			stack: 2,
+			height: 2,
+		};
+		assert_eq!(
+			check(&runner, problem),
+			"Instruction 3 (move 4 from 2 to 1): \
+			Can't stack dirt, Claus! Stack 2 only has 2 crates"
+		);
+
+		runner.tasks[2].source = 0;
+		check(&runner, Problem::NoSuchStack(0));
+		runner.tasks[2].source = 2;
+		runner.tasks[2].dest = 4;
+		let message = check(&runner, Problem::NoSuchStack(4));
+		assert_eq!(
+			message,
+			"Instruction 3 (move 4 from 2 to 4): there's no stack 4"
+		);
+	}
+
+	#[test]
+	fn test_trace() {
+		let runner = Day05::parse_input(EXAMPLE);
+		let mut steps = Vec::new();
+		let stacks = runner.trace(&CrateMover9001, |task, stacks| {
+			steps.push((task.to_string(), stacks.tops()));
+		});
+
+		assert_eq!(stacks.unwrap().tops(), "MCD");
+		let expected = [
+			("move 1 from 2 to 1", "DCP"),
+			("move 3 from 1 to 3", "CD"),
+			("move 2 from 2 to 1", "CD"),
+			("move 1 from 1 to 2", "MCD"),
+		];
+		let expected = expected.map(|(a, b)| (a.to_owned(), b.to_owned()));
+		assert_eq!(steps, expected);
+	}
+
+	#[test]
+	fn test_plan() {
+		let runner = Day05::parse_input(EXAMPLE);
+		let stacks = &runner.stacks;
+
+		assert_eq!(stacks.plan(&CrateMover9000, "NDP", 0), Some(vec![]));
+		let expected = Task {
+			count: 1,
+			source: 1,
+			dest: 2,
+		};
+		assert_eq!(
+			stacks.plan(&CrateMover9000, "ZNP", 3),
+			Some(vec![expected])
+		);
+		assert_eq!(stacks.plan(&CrateMover9000, "XYZ", 3), None);
+
+		for (crane, target) in [
+			(&CrateMover9000 as &dyn Crane, "CMZ"),
+			(&CrateMover9001, "MCD"),
+		] {
+			let plan = stacks.plan(crane, target, 4).unwrap();
+			// Following the plan gets there...
+			let mut end = stacks.clone();
+			for task in &plan {
+				end.apply(crane, task).unwrap();
+			}
+			assert_eq!(end.tops(), target);
+			// ...and nothing shorter does.
+			assert!(plan.len() <= runner.tasks.len());
+			assert_eq!(stacks.plan(crane, target, plan.len() - 1), None);
+		}
+	}
+
+	#[test]
+	fn test_sketch() {
+		let stacks = SKETCH.parse::<Stacks>().unwrap();
+		let expected = vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']];
+		assert_eq!(stacks, Stacks(expected));
+		assert_eq!(stacks.to_string(), SKETCH);
+
+		// Without the trailing spaces, it's the same stacks.
+		let trimmed = SKETCH.lines().map(str::trim_end).collect::<Vec<_>>();
+		assert_eq!(trimmed.join("\n").parse(), Ok(stacks));
+	}
+
+	#[test]
+	fn test_bad_sketches() {
+		let bad = [
+			"",
+			"[A]",
+			"[A]\n 2 ",
+			"[A] [B]\n 1 ",
+			"[A] {B}\n 1   2 ",
+			"[A]\n    [B]\n 1   2 ",
+			"[AB]\n 1 ",
+		];
+		for sketch in bad {
+			assert!(sketch.parse::<Stacks>().is_err(), "{:?}", sketch);
+		}
+		// Empty stacks are fine, though.
+		assert_eq!(
+			"    [B]\n 1   2 ".parse(),
+			Ok(Stacks(vec![vec![], vec!['B']]))
+		);
+	}
+
+	#[test]
+	fn test_round_trip() {
+		// Stacks from the bottom up. Empty ones are the awkward part, since
+		// their columns are nothing but spaces.
+		let layouts: [&[&str]; 9] = [
+			&[""],
+			&["A"],
+			&["ZN", "MCD", "P"],
+			&["", "B"],
+			&["AB", ""],
+			&["", "", ""],
+			&["QWERTYUI", "", "Z", "ABCDEFG"],
+			&["A", "B", "C", "D", "E", "F", "G", "H", "I"],
+			&["", "XY", "", "", "PQRSTUVW", "", "", "K", ""],
+		];
+		let mut all = layouts
+			.iter()
+			.map(|layout| {
+				Stacks(layout.iter().map(|s| s.chars().collect()).collect())
+			})
+			.collect::<Vec<_>>();
+
+		// Plus a little deterministic mess, since there's no proptest here.
+		let mut rng = Rng::new(5);
+		for _ in 0..500 {
+			let stacks = (0..1 + rng.below(9))
+				.map(|_| {
+					(0..rng.below(8))
+						.map(|_| (b'A' + rng.below(26) as u8) as char)
+						.collect()
+				})
+				.collect();
+			all.push(Stacks(stacks));
+		}
+		for stacks in all {
+			let sketch = stacks.to_string();
+			let parsed = sketch.parse::<Stacks>();
+			assert_eq!(parsed.as_ref(), Ok(&stacks), "{}", sketch);
+			assert_eq!(parsed.unwrap().to_string(), sketch);
+		}
+	}
 }
@@ -9,83 +9,472 @@
 //! Part 2
 //! ------
 //! Find the index of the first sequence of _fourteen_ unique characters.
+//!
+//! Markers
+//! -------
+//! `markers [name=size...]` reads the whole stream and lists every marker it
+//! can find, not just the first, for as many protocols as you like. Without
+//! any, it looks for packets and messages.
+
+use std::collections::VecDeque;
+use std::env;
+use std::ops::ControlFlow;
+use std::process;
 
-use std::io::{stdin, Read};
+use advent::streaming::{parse_reader, Records, StreamingAdvent};
+use advent::{input_to_str, param_value, Advent, Runner};
 
 // Constants for the size of the area needed for each step.
 // The names are chosen as per the narrative around the puzzle.
-const PACKET_SIZE: usize = 4;
-const MESSAGE_SIZE: usize = 14;
+/// How long a start-of-packet marker is.
+const PACKET_SIZE: i64 = 4;
+/// How long a start-of-message marker is.
+const MESSAGE_SIZE: i64 = 14;
 
-fn main() {
-	let bytes = stdin().bytes();
-	let mut marker: Vec<_> = vec![];
-	let mut packet = 0;
-	let mut message = 0;
-	let mut part1 = false;
-	let mut part2 = false;
-	// Since the puzzle for today wants to know where in the input
-	// the marker is, we're using `enumerate` today. Note that
-	// this method, like all indexing in Rust, starts at zero.
-	// This means that I'm going to have to remember to adjust for the
-	// inevitable off by one error. Insert the relevant Serge emote here.
-	for (i, byte) in bytes.enumerate() {
-		if let Ok(ch) = byte {
-			// Add the current character to the data stream.
-			marker.push(ch);
-			// Check to see if we haven't already hit the packet.
-			if marker.len() >= PACKET_SIZE && !part1 {
-				// Run the uniqueness logic here. If this returns true,
-				// we no longer need to run the logic here again.
-				part1 = is_window_unique(&marker, PACKET_SIZE);
-				// If the logic returns true, then we've found the answer
-				// to Part 1.
-				if part1 {
-					packet = i + 1;
+#[derive(Debug, PartialEq, Eq)]
+struct Day06 {
+	/// How far into the stream the packet marker ends, if there is one.
+	packet: Option<usize>,
+	/// How far into the stream the message marker ends, if there is one.
+	message: Option<usize>,
+}
+
+/// What we need to remember while the data stream is coming in: one
+/// detector for each kind of marker, both fed from the same pass.
+///
+/// The marker sizes are parameters, and they're needed before the first
+/// character shows up, so they get set here rather than on [`Day06`].
+#[derive(Debug)]
+struct Scanner {
+	packet: MarkerDetector,
+	message: MarkerDetector,
+}
+
+impl Default for Scanner {
+	fn default() -> Self {
+		Scanner {
+			packet: MarkerDetector::new(PACKET_SIZE as usize),
+			message: MarkerDetector::new(MESSAGE_SIZE as usize),
+		}
+	}
+}
+
+/// Looks for the first run of `size` characters that are all different.
+///
+/// Since the stream could be enormous, this only hangs on to the last `size`
+/// characters, and keeps a count of how many of each are in there. Sliding
+/// the window along only touches the counts for the characters going in and
+/// out, so every character takes the same time no matter how big the window
+/// is.
+#[derive(Debug)]
+struct MarkerDetector {
+	size: usize,
+	/// The most recent characters in the stream.
+	window: VecDeque<u8>,
+	/// How many times each character shows up in the window.
+	counts: [usize; 256],
+	/// How many different characters show up more than once in the window.
+	repeats: usize,
+	/// How many characters we've read so far.
+	position: usize,
+	/// Where the marker ended, once we've found it.
+	found: Option<usize>,
+}
+
+impl MarkerDetector {
+	/// Make a detector for markers `size` characters long. A marker of
+	/// nothing isn't much of a marker, so `size` can't be zero.
+	fn new(size: usize) -> Self {
+		assert_ne!(size, 0, "A marker needs at least one character");
+		MarkerDetector {
+			size,
+			window: VecDeque::with_capacity(size + 1),
+			counts: [0; 256],
+			repeats: 0,
+			position: 0,
+			found: None,
+		}
+	}
+
+	/// Feed in the next character. Returns where the marker ends, counting
+	/// characters from one, as soon as there is one. Once it's been found,
+	/// this just keeps saying so.
+	fn push(&mut self, ch: u8) -> Option<usize> {
+		if self.found.is_none() && self.slide(ch) {
+			self.found = Some(self.position);
+		}
+		self.found
+	}
+
+	/// Feed in the next character, whether or not we've found a marker
+	/// already. Returns whether the window is a marker right now.
+	fn slide(&mut self, ch: u8) -> bool {
+		// Note that the answer wants a count of characters, not an index,
+		// so this gets bumped before we check anything.
+		self.position += 1;
+		self.window.push_back(ch);
+		self.counts[ch as usize] += 1;
+		if self.counts[ch as usize] == 2 {
+			self.repeats += 1;
+		}
+		// Forget whatever's too old to matter.
+		if self.window.len() > self.size {
+			if let Some(old) = self.window.pop_front() {
+				self.counts[old as usize] -= 1;
+				if self.counts[old as usize] == 1 {
+					self.repeats -= 1;
 				}
 			}
-			// Part 2 is much the same, just with a different number.
-			if marker.len() >= MESSAGE_SIZE && !part2 {
-				part2 = is_window_unique(&marker, MESSAGE_SIZE);
-				if part2 {
-					message = i + 1;
-				}
+		}
+		self.window.len() == self.size && self.repeats == 0
+	}
+}
+
+/// Every marker for one protocol. A protocol is just a name for a marker
+/// size, as far as I can tell, but the elves seem to have a lot of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Sightings {
+	name: String,
+	size: usize,
+	/// Where each marker ends, counting characters from one, like the
+	/// answers. The markers themselves are still sitting in the stream.
+	ends: Vec<usize>,
+}
+
+impl Sightings {
+	/// Where the marker the puzzle actually wants ends.
+	fn first(&self) -> Option<usize> {
+		self.ends.first().copied()
+	}
+
+	fn count(&self) -> usize {
+		self.ends.len()
+	}
+
+	/// The characters of the marker ending at `end`, fished back out of the
+	/// stream it was found in.
+	fn marker<'a>(&self, stream: &'a [u8], end: usize) -> &'a [u8] {
+		&stream[end - self.size..end]
+	}
+}
+
+/// Find every marker for every protocol, all in one pass over the stream.
+fn survey(stream: &[u8], protocols: &[(&str, usize)]) -> Vec<Sightings> {
+	let mut detectors = protocols
+		.iter()
+		.map(|&(_, size)| MarkerDetector::new(size))
+		.collect::<Vec<_>>();
+	let mut sightings = protocols
+		.iter()
+		.map(|&(name, size)| Sightings {
+			name: name.to_string(),
+			size,
+			ends: Vec::new(),
+		})
+		.collect::<Vec<_>>();
+	for &ch in stream {
+		for (detector, sighting) in detectors.iter_mut().zip(&mut sightings) {
+			if detector.slide(ch) {
+				sighting.ends.push(detector.position);
 			}
+		}
+	}
+	sightings
+}
+
+/// Turn `name=size` arguments into protocols.
+fn parse_protocols(args: &[String]) -> Result<Vec<(&str, usize)>, String> {
+	if args.is_empty() {
+		return Ok(vec![
+			("packet", PACKET_SIZE as usize),
+			("message", MESSAGE_SIZE as usize),
+		]);
+	}
+	args.iter()
+		.map(|arg| {
+			let (name, size) = arg
+				.split_once('=')
+				.ok_or_else(|| format!("Expected name=size, got {:?}", arg))?;
+			match size.parse() {
+				Ok(size) if size > 0 => Ok((name, size)),
+				_ => Err(format!("{:?} isn't a marker size", size)),
+			}
+		})
+		.collect()
+}
+
+impl Advent for Day06 {
+	type Answer1 = usize;
+
+	type Answer2 = usize;
+
+	const PARAMS: &'static [(&'static str, i64)] =
+		&[("packet_size", PACKET_SIZE), ("message_size", MESSAGE_SIZE)];
+
+	fn parse_input(input: &str) -> Self {
+		parse_reader(input.as_bytes()).expect("Reading from memory failed?")
+	}
+
+	fn part_one(&self) -> usize {
+		self.packet
+			.expect("There's no packet marker in this stream!")
+	}
+
+	fn part_two(&self) -> usize {
+		self.message
+			.expect("There's no message marker in this stream!")
+	}
+}
+
+impl StreamingAdvent for Day06 {
+	type State = Scanner;
+
+	const RECORDS: Records = Records::Bytes;
+
+	fn consume(scanner: &mut Scanner, record: &[u8]) -> ControlFlow<()> {
+		// Records are single bytes, but there's no harm in being general.
+		for &ch in record {
+			let packet = scanner.packet.push(ch);
+			// Part 2 is much the same, just with a different number.
+			let message = scanner.message.push(ch);
 			// If both parts have returned an answer, then we don't need to
-			// keep looping and should bail.
-			if part1 && part2 {
-				break;
+			// keep reading and should bail.
+			if packet.is_some() && message.is_some() {
+				return ControlFlow::Break(());
 			}
-		} else {
-			// "Handle" error because I dislike `flatten` as a method.
-			// The unwrap is safe because there's no way to get an `Ok` here
-			// because we just failed a pattern match on that state.
-			panic!("Welp, your input has failed: {}. Oops!", byte.unwrap_err());
 		}
+		ControlFlow::Continue(())
+	}
+
+	fn finish(scanner: Scanner) -> Self {
+		Day06 {
+			packet: scanner.packet.found,
+			message: scanner.message.found,
+		}
+	}
+
+	fn set_state_param(
+		scanner: &mut Scanner,
+		name: &str,
+		value: i64,
+	) -> Result<(), String> {
+		let size = param_value(name, value)?;
+		if size == 0 {
+			return Err(String::from("A marker needs at least one character"));
+		}
+		match name {
+			"packet_size" => scanner.packet = MarkerDetector::new(size),
+			"message_size" => scanner.message = MarkerDetector::new(size),
+			_ => return Err(format!("Unknown parameter {}", name)),
+		}
+		Ok(())
 	}
-	println!("The packet marker can be found at index {}", packet);
-	println!("The message marker can be found at index {}", message);
 }
 
-/// Check whether the last `size` elements of this array are unique.
-fn is_window_unique<T>(marker: &[T], size: usize) -> bool
-where
-	T: Ord,
-{
-	let mut set = marker
-		.iter() // Create an iterator.
-		.rev() // Reverse the iterator so that the back is more accessible.
-		.take(size) // Grab the number of elements we actually care about.
-		.collect::<Vec<_>>(); // Convert the iterator into a usable type.
-
-	// Sort the vector. I'm kind of annoyed that this is in place.
-	// I get WHY, but it's annoying that attaching `.sort` to something
-	// results in a value that I can't use.
-	set.sort();
-	// Turns out this is more type safe than just using `HashSet`.
-	set.dedup();
-	// Check that our window is the size we need. If this is the same
-	// size as before deduplication, that means that nothing has been removed
-	// and thus, every element in this window is unique!
-	set.len() == size
+fn main() {
+	let args = env::args().skip(1).collect::<Vec<_>>();
+	if args.first().map(String::as_str) == Some("markers") {
+		match parse_protocols(&args[1..]) {
+			Ok(protocols) => markers(&protocols),
+			Err(err) => {
+				eprintln!("{}\nUsage: markers [name=size...]", err);
+				process::exit(2);
+			}
+		}
+		return;
+	}
+	let mut runner = Runner::<Day06>::from_stdin_streaming();
+	println!(
+		"The packet marker can be found at index {}",
+		runner.part_one()
+	);
+	println!(
+		"The message marker can be found at index {}",
+		runner.part_two()
+	);
+	runner.report();
+}
+
+/// Handle the `markers` subcommand.
+fn markers(protocols: &[(&str, usize)]) {
+	let input = input_to_str();
+	let stream = input.trim_end().as_bytes();
+	for sightings in survey(stream, protocols) {
+		match sightings.first() {
+			Some(first) => println!(
+				"{} ({}): {} markers, the first ending at {}",
+				sightings.name,
+				sightings.size,
+				sightings.count(),
+				first
+			),
+			None => println!(
+				"{} ({}): no markers at all",
+				sightings.name, sightings.size
+			),
+		}
+		for &end in &sightings.ends {
+			let marker = sightings.marker(stream, end);
+			println!("{:>8} {}", end, String::from_utf8_lossy(marker));
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use advent::rng::Rng;
+
+	#[test]
+	fn test_examples() {
+		let examples = [
+			("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+			("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+			("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+			("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+			("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+		];
+		for (example, packet, message) in examples {
+			let runner = Day06::parse_input(example);
+			assert_eq!(runner.part_one(), packet, "{}", example);
+			assert_eq!(runner.part_two(), message, "{}", example);
+		}
+	}
+
+	/// Check whether the last `size` elements of this array are unique, the
+	/// slow way. This is how the puzzle used to get solved.
+	fn is_window_unique<T>(marker: &[T], size: usize) -> bool
+	where
+		T: Ord,
+	{
+		let mut set = marker.iter().rev().take(size).collect::<Vec<_>>();
+		set.sort();
+		set.dedup();
+		set.len() == size
+	}
+
+	#[test]
+	fn test_against_sorting() {
+		// The puzzle's examples, plus some with a lot of repeats and some
+		// with none at all.
+		let streams = [
+			"",
+			"a",
+			"aaaa",
+			"aab",
+			"abcabcabcabc",
+			"abacabadabacabaeabacabadabacaba",
+			"abcdefghijklmnopqrstuvwxyz",
+			"zyxwvutsrqponmlkjihgfedcbaabc",
+			"aaaaaaaaaaaaaaaabcdefghijklmnopq",
+			"mjqjpqmgbljsphdztnvjfqwrcgsmlb",
+			"bvwbjplbgvbhsrlpgdmjqwftvncz",
+			"nppdvjthqldpwncqszvftbrmjlhg",
+			"nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg",
+			"zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw",
+		];
+		let mut all = streams
+			.iter()
+			.map(|stream| stream.as_bytes().to_vec())
+			.collect::<Vec<_>>();
+
+		// And a little deterministic mess, from small alphabets so that
+		// there are plenty of repeats.
+		let mut rng = Rng::new(6);
+		for _ in 0..200 {
+			let letters = 2 + rng.below(20);
+			all.push(
+				(0..rng.below(100))
+					.map(|_| b'a' + rng.below(letters) as u8)
+					.collect(),
+			);
+		}
+		for stream in all {
+			for size in 1..=16 {
+				let expected = (size..=stream.len())
+					.find(|&end| is_window_unique(&stream[..end], size));
+				let mut detector = MarkerDetector::new(size);
+				let found =
+					stream.iter().filter_map(|&ch| detector.push(ch)).next();
+				assert_eq!(found, expected, "{:?} {}", stream, size);
+			}
+		}
+	}
+
+	#[test]
+	fn test_survey() {
+		let stream = b"mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+		let sightings = survey(stream, &[("packet", 4), ("pair", 2)]);
+		assert_eq!(sightings[0].name, "packet");
+		assert_eq!(sightings[0].size, 4);
+		assert_eq!(sightings[0].first(), Some(7));
+		assert_eq!(sightings[0].marker(stream, 7), b"jpqm");
+		// Once it gets going, the rest of the stream is one long marker.
+		assert_eq!(sightings[0].ends, (7..=30).collect::<Vec<_>>());
+		// No character ever follows itself, so every pair counts.
+		assert_eq!(sightings[1].ends, (2..=30).collect::<Vec<_>>());
+		assert_eq!(sightings[1].marker(stream, 2), b"mj");
+	}
+
+	#[test]
+	fn test_survey_agrees_with_first() {
+		let examples = [
+			("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+			("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+			("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+		];
+		let protocols = [("packet", 4), ("message", 14)];
+		for (example, packet, message) in examples {
+			let sightings = survey(example.as_bytes(), &protocols);
+			assert_eq!(sightings[0].first(), Some(packet));
+			assert_eq!(sightings[1].first(), Some(message));
+			// Anything counted should really be all different.
+			for sighting in &sightings {
+				for &end in &sighting.ends {
+					let marker = sighting.marker(example.as_bytes(), end);
+					assert!(is_window_unique(marker, sighting.size));
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_parse_protocols() {
+		let args = ["sync=3".to_string(), "long=20".to_string()];
+		assert_eq!(parse_protocols(&args), Ok(vec![("sync", 3), ("long", 20)]));
+		assert_eq!(
+			parse_protocols(&[]),
+			Ok(vec![("packet", 4), ("message", 14)])
+		);
+		assert!(parse_protocols(&["sync".to_string()]).is_err());
+		assert!(parse_protocols(&["sync=0".to_string()]).is_err());
+		assert!(parse_protocols(&["sync=x".to_string()]).is_err());
+	}
+
+	#[test]
+	fn test_params() {
+		use advent::streaming::{parse_reader_from, set_state_param};
+
+		let mut scanner = Scanner::default();
+		set_state_param::<Day06>(&mut scanner, "packet_size", 2).unwrap();
+		set_state_param::<Day06>(&mut scanner, "message_size", 5).unwrap();
+		let stream = "mjqjpqmgbljsphdztnvjfqwrcgsmlb".as_bytes();
+		let runner = parse_reader_from::<Day06, _>(scanner, stream).unwrap();
+		assert_eq!(runner.part_one(), 2);
+		assert_eq!(runner.part_two(), 8);
+
+		let mut scanner = Scanner::default();
+		for (name, value) in [("packet_size", 0), ("message_size", -1)] {
+			let result = set_state_param::<Day06>(&mut scanner, name, value);
+			assert!(result.is_err(), "{}={}", name, value);
+		}
+		assert!(set_state_param::<Day06>(&mut scanner, "nope", 4).is_err());
+	}
+
+	#[test]
+	fn test_no_marker() {
+		let runner = Day06::parse_input("abcabcabcabcabcabc");
+		assert_eq!(runner.packet, None);
+		assert_eq!(runner.message, None);
+	}
 }
@@ -13,143 +13,248 @@
 //! ------
 //! Now it's time to clean up space. Find the smallest directory that will
 //! give us enough space, so we don't `rm -rf /` like a dolt.
+//!
+//! Looking around
+//! --------------
+//! `tree` draws the filesystem like the puzzle does, and `du [depth]` lists
+//! directory sizes biggest first, like `du -h --max-depth=<depth>`. Handy
+//! for checking the transcript made sense.
+
+use std::env;
+use std::process;
 
-use std::cell::RefCell;
-use std::collections::HashMap;
-use std::io::stdin;
-use std::rc::Rc;
+use advent::vfs::{FsError, VirtualFs};
+use advent::{input_to_str, param_value, Advent, Runner};
 
 /// The size of directory we care about.
-const SMALL_DIRECTORY: usize = 100_000;
-const TOTAL_DISK_SPACE: usize = 70_000_000;
-const SPACE_NEEDED: usize = 30_000_000;
-
-#[derive(Clone, Debug, PartialEq)]
-/// Represents a file on disk. This could probably be attached to its
-/// directory, but I don't want to attach a lifetime to this right now.
-struct File {
-	path: String,
-	size: usize,
-	name: String,
+const SMALL_DIRECTORY: i64 = 100_000;
+/// How big the disk is.
+const TOTAL_DISK_SPACE: i64 = 70_000_000;
+/// How much free space the update wants.
+const SPACE_NEEDED: i64 = 30_000_000;
+
+/// Today's code used to involve a good deal of theft. Now the filesystem
+/// does the heavy lifting, and all that's left is adding things up.
+#[derive(Debug)]
+struct Day07 {
+	fs: VirtualFs,
+	/// The biggest a directory can be and still count for Part 1. Defaults
+	/// to [`SMALL_DIRECTORY`].
+	small_directory: usize,
+	/// Defaults to [`TOTAL_DISK_SPACE`].
+	total_disk_space: usize,
+	/// Defaults to [`SPACE_NEEDED`].
+	space_needed: usize,
 }
 
-type NodeRef = Rc<RefCell<Node>>;
+impl Day07 {
+	/// Replay a transcript, and get ready to answer questions about it with
+	/// the parameters at their defaults.
+	fn replay(input: &str) -> Result<Self, FsError> {
+		Ok(Day07 {
+			fs: VirtualFs::replay(input)?,
+			small_directory: SMALL_DIRECTORY as usize,
+			total_disk_space: TOTAL_DISK_SPACE as usize,
+			space_needed: SPACE_NEEDED as usize,
+		})
+	}
 
-#[derive(Clone, Debug, PartialEq)]
-/// Represents an item in the filesystem. In true Unix fashion,
-/// this could represent a fire or directory.
-struct Node {
-	size: Option<usize>,
-	children: HashMap<String, NodeRef>,
-	parent: Option<NodeRef>,
-	is_file: bool,
+	/// Every directory's size, in no particular order.
+	fn sizes(&self) -> impl Iterator<Item = usize> + '_ {
+		self.fs.directories().map(|(_, id)| self.fs.size(id))
+	}
 }
 
-impl Node {
-	fn root() -> Self {
-		Node {
-			size: None,
-			children: HashMap::new(),
-			parent: None,
-			is_file: false,
+impl Advent for Day07 {
+	type Answer1 = usize;
+
+	type Answer2 = usize;
+
+	const PARAMS: &'static [(&'static str, i64)] = &[
+		("small_directory", SMALL_DIRECTORY),
+		("total_disk_space", TOTAL_DISK_SPACE),
+		("space_needed", SPACE_NEEDED),
+	];
+
+	/// There's no saying no from here, so the binary uses
+	/// [`Day07::replay`] instead, and reports a bad transcript properly.
+	fn parse_input(input: &str) -> Self {
+		Day07::replay(input).unwrap_or_else(|err| panic!("{}", err))
+	}
+
+	fn part_one(&self) -> usize {
+		self.sizes()
+			.filter(|&size| size <= self.small_directory)
+			.sum()
+	}
+
+	fn part_two(&self) -> usize {
+		// `set_param` makes sure everything fits on the disk, and that the
+		// update does too, so deleting `/` would always be enough.
+		let used = self.fs.size(self.fs.root());
+		let free = self
+			.total_disk_space
+			.checked_sub(used)
+			.expect("This disk is fuller than full");
+		let needed = self.space_needed.saturating_sub(free);
+		self.sizes()
+			.filter(|&size| size >= needed)
+			.min()
+			.expect("Welp, you need a new computer. Sorry!")
+	}
+
+	fn set_param(&mut self, name: &str, value: i64) -> Result<(), String> {
+		let value = param_value(name, value)?;
+		let used = self.fs.size(self.fs.root());
+		match name {
+			"small_directory" => self.small_directory = value,
+			"total_disk_space" if value < used => {
+				return Err(format!(
+					"There's already {} on the disk, so it can't be {}",
+					used, value
+				));
+			}
+			"total_disk_space" if value < self.space_needed => {
+				return Err(format!(
+					"The update needs {}, so the disk can't be {}",
+					self.space_needed, value
+				));
+			}
+			"total_disk_space" => self.total_disk_space = value,
+			"space_needed" if value > self.total_disk_space => {
+				return Err(format!(
+					"The disk is only {}, so the update can't need {}",
+					self.total_disk_space, value
+				));
+			}
+			"space_needed" => self.space_needed = value,
+			_ => return Err(format!("Unknown parameter {}", name)),
 		}
+		Ok(())
 	}
 }
 
-// Today's code involves a good deal of theft, unfortunately.
 fn main() {
-	let lines = stdin().lines().filter_map(|l| match l {
-		Ok(line) => Some(line),
-		Err(err) => panic!("Welp, your input failed: {}", err),
-	});
-	let root = Rc::new(RefCell::new(Node::root()));
-	let mut cur_node = Rc::clone(&root);
-	for line in lines {
-		let tokens: Vec<_> = line.split(' ').collect();
-		if tokens[0] == "$" {
-			match tokens[1] {
-				"cd" => {
-					let folder = tokens[2];
-					cur_node = match folder {
-						".." => Rc::clone(
-							cur_node.borrow().parent.as_ref().unwrap_or_else(
-								|| panic!("Has anybody seen {}'s mom?", folder),
-							),
-						),
-						"/" => root.clone(),
-						_ => cur_node
-							.borrow()
-							.children
-							.get(folder)
-							.unwrap_or_else(|| {
-								panic!(
-									"Looking for a {}, has anyone seen it?",
-									folder
-								)
-							})
-							.clone(),
-					};
-				}
-				"ls" => {
-					// This does absolutely nothing.
-				}
-				command => panic!("{} is not a valid command.", command),
-			}
-		} else {
-			let size_or_dir = tokens[0];
-			let name = tokens[1];
-			if !cur_node.borrow().children.contains_key(name) {
-				let child = Rc::new(RefCell::new(Node::root()));
-				let mut mut_child = child.borrow_mut();
-				if size_or_dir != "dir" {
-					mut_child.is_file = true;
-					mut_child.size = Some(
-						size_or_dir
-							.parse()
-							.expect("Sizes are in decimal, not base64!"),
-					);
+	let args = env::args().skip(1).collect::<Vec<_>>();
+	match args.first().map(String::as_str) {
+		Some("tree") if args.len() == 1 => {
+			print!("{}", replay_stdin().tree());
+			return;
+		}
+		Some("du") => {
+			let max_depth = match args.get(1).map(|arg| arg.parse()) {
+				None => None,
+				Some(Ok(depth)) if args.len() == 2 => Some(depth),
+				_ => {
+					eprintln!("Usage: du [depth]");
+					process::exit(2);
 				}
-				mut_child.parent = Some(Rc::clone(&cur_node));
-				cur_node
-					.borrow_mut()
-					.children
-					.insert(name.to_owned(), Rc::clone(&child));
-			}
+			};
+			print!("{}", replay_stdin().du(max_depth));
+			return;
 		}
+		_ => {}
 	}
-	let mut sizes: Vec<usize> = vec![];
-	let borrowed = root.borrow();
-	let (cur_size, sizes) = calc_sum(&borrowed, &mut sizes);
-	let res: usize = sizes.iter().filter(|&s| *s < SMALL_DIRECTORY).sum();
-	println!("Total sum of all of the small directories is {}", res);
-	let needed = SPACE_NEEDED - (TOTAL_DISK_SPACE - cur_size);
-	let dead_dir = sizes.iter().filter(|&x| *x > needed).min();
-	match dead_dir {
-		Some(dead_size) => {
-			println!("Good news, you can clear up {}!", dead_size)
+	let mut runner = Runner::try_from_stdin(Day07::replay);
+	println!(
+		"Total sum of all of the small directories is {}",
+		runner.part_one()
+	);
+	println!("Good news, you can clear up {}!", runner.part_two());
+	runner.report();
+}
+
+/// Rebuild the filesystem from the transcript on stdin. A transcript that
+/// doesn't make sense isn't worth a panic, so this complains and exits
+/// instead.
+fn replay_stdin() -> VirtualFs {
+	match VirtualFs::replay(&input_to_str()) {
+		Ok(fs) => fs,
+		Err(err) => {
+			eprintln!("{}", err);
+			process::exit(1);
 		}
-		None => panic!("Welp, you need a new computer. Sorry!"),
 	}
 }
 
-/// And today we get to play with _recursive_ functions!
-/// This calculates the sum we need for the Advent of Code answer.
-fn calc_sum<'a>(
-	node: &'a Node,
-	sizes: &'a mut Vec<usize>,
-) -> (usize, &'a mut Vec<usize>) {
-	if node.is_file {
-		return (node.size.expect("Is this /dev/null?"), sizes);
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k";
+
+	#[test]
+	fn test_example() {
+		let runner = Day07::parse_input(EXAMPLE);
+		assert_eq!(runner.part_one(), 95437);
+		assert_eq!(runner.part_two(), 24933642);
+	}
+
+	#[test]
+	fn test_params() {
+		use advent::set_param;
+
+		let mut runner = Day07::parse_input(EXAMPLE);
+		set_param(&mut runner, "small_directory", 1000).unwrap();
+		assert_eq!(runner.part_one(), 584);
+		// There's 21,618,835 free already, so this only needs 50,000 more.
+		// /d is overkill, and /a does the job.
+		set_param(&mut runner, "space_needed", 21_668_835).unwrap();
+		assert_eq!(runner.part_two(), 94853);
+		// With a bigger disk there's already room, so anything will do.
+		set_param(&mut runner, "total_disk_space", 80_000_000).unwrap();
+		assert_eq!(runner.part_two(), 584);
+		assert!(set_param(&mut runner, "space_needed", -1).is_err());
+
+		// Nothing that would leave part two without an answer.
+		let mut runner = Day07::parse_input(EXAMPLE);
+		assert!(set_param(&mut runner, "total_disk_space", 1).is_err());
+		assert!(set_param(&mut runner, "total_disk_space", 48_381_164).is_err());
+		assert!(set_param(&mut runner, "space_needed", 70_000_001).is_err());
+		set_param(&mut runner, "space_needed", 60_000_000).unwrap();
+		assert!(set_param(&mut runner, "total_disk_space", 50_000_000).is_err());
+		set_param(&mut runner, "space_needed", SPACE_NEEDED).unwrap();
+		// A disk that's exactly full means everything has to go.
+		set_param(&mut runner, "total_disk_space", 48_381_165).unwrap();
+		assert_eq!(runner.part_two(), 48_381_165);
+		assert!(set_param(&mut runner, "space_needed", 48_381_166).is_err());
+		set_param(&mut runner, "space_needed", 48_381_165).unwrap();
+		assert!(set_param(&mut runner, "total_disk_space", 48_381_164).is_err());
+	}
+
+	#[test]
+	fn test_lost() {
+		let error = Day07::replay("$ cd /\n$ cd nowhere").unwrap_err();
+		let expected = FsError::UnknownDirectory {
+			line: 2,
+			name: "nowhere".to_string(),
+		};
+		assert_eq!(error, expected);
+		assert_eq!(
+			error.to_string(),
+			"Line 2: looking for \"nowhere\", has anyone seen it?"
+		);
 	}
-	let sum_c = node
-		// Get the children of this node
-		.children
-		// Get just the sizes.
-		.values()
-		// Map this function onto the children.
-		.map(|c| calc_sum(&c.borrow(), sizes).0)
-		// Add them all together.
-		.sum();
-	sizes.push(sum_c); // Add this result to the size list.
-	(sum_c, sizes)
 }
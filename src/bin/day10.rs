@@ -15,7 +15,8 @@
 
 use std::fmt;
 
-use advent::{input_to_str, Advent};
+use advent::repl::{self, parse_arg, Command, Repl};
+use advent::{Advent, Runner};
 
 #[derive(Debug, PartialEq, Eq)]
 struct Day10(Vec<Instruction>);
@@ -163,14 +164,33 @@ impl Day10 {
 	}
 }
 
+impl Repl for Day10 {
+	fn commands() -> Vec<Command<Self>> {
+		vec![Command {
+			name: "cycles",
+			args: "<cycle>...",
+			help: "Print the register during each of the given cycles",
+			run: |runner, args| {
+				let critical = (0..args.len())
+					.map(|idx| parse_arg(args, idx, "cycle"))
+					.collect::<Result<Vec<i32>, _>>()?;
+				Ok(format!("{:?}", runner.execute(&critical)))
+			},
+		}]
+	}
+}
+
 fn main() {
-	let input = input_to_str();
-	let code = Day10::parse_input(&input);
-	let one = code.part_one();
+	if repl::launch::<Day10>() {
+		return;
+	}
+	let mut runner = Runner::<Day10>::from_stdin();
+	let one = runner.part_one();
 	println!("The sum of the important cycles is {}", one);
 
 	println!("The output of part 2 is:");
-	println!("{}", code.part_two());
+	println!("{}", runner.part_two());
+	runner.report();
 }
 
 #[cfg(test)]
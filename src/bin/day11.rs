@@ -8,7 +8,9 @@
 //! ------
 //! After 20 rounds of Monkey Business, what level of chaos have we reached?
 
-use advent::{input_to_str, Advent};
+use advent::math::lcm;
+use advent::repl::{self, parse_arg, Command, Repl};
+use advent::{param_value, Advent, Checked, Runner};
 
 /// An operation that a monkey can apply.
 ///
@@ -42,7 +44,9 @@ impl Monkey {
 		let throws = self
 			.items
 			.iter()
-			.map(|item| {
+			.map(|&item| {
+				// Worry levels grow fast, so don't let them wrap around.
+				let item = Checked(item);
 				let worry = match self.operation {
 					Operation::Add(val) => match val {
 						Some(val) => item + val,
@@ -55,7 +59,7 @@ impl Monkey {
 				};
 
 				// Divide worry level by three, rounding down.
-				let worry = worry / 3;
+				let worry = (worry / 3).0;
 
 				let target = match worry % self.factor {
 					0 => self.decision.0,
@@ -83,8 +87,20 @@ struct Throw {
 	dest: usize,
 }
 
+/// How many rounds Part 1 plays.
+const ROUNDS: i64 = 20;
+/// How many rounds Part 2 plays, now that we're not relieved anymore.
+const ANXIOUS_ROUNDS: i64 = 10_000;
+
 #[derive(Clone, Debug)]
-struct Day11(Vec<Monkey>);
+struct Day11 {
+	/// The monkeys, in the order they're listed.
+	monkeys: Vec<Monkey>,
+	/// How many rounds Part 1 plays. Defaults to [`ROUNDS`].
+	rounds: usize,
+	/// How many rounds Part 2 plays. Defaults to [`ANXIOUS_ROUNDS`].
+	anxious_rounds: usize,
+}
 
 // String constants used for parsing.
 const OPERATION_PREFIX: &str = "  Operation: new = old";
@@ -97,6 +113,9 @@ impl Advent for Day11 {
 
 	type Answer2 = usize;
 
+	const PARAMS: &'static [(&'static str, i64)] =
+		&[("rounds", ROUNDS), ("anxious_rounds", ANXIOUS_ROUNDS)];
+
 	fn parse_input(input: &str) -> Self {
 		let mut lines = input.lines();
 		let mut monkeys = Vec::new();
@@ -190,22 +209,20 @@ impl Advent for Day11 {
 			monkeys.push(monkey);
 		}
 
-		Day11(monkeys)
+		Day11 {
+			monkeys,
+			rounds: ROUNDS as usize,
+			anxious_rounds: ANXIOUS_ROUNDS as usize,
+		}
 	}
 
 	fn part_one(&self) -> usize {
-		let mut monkeys = self.0.clone();
+		let mut monkeys = self.monkeys.clone();
 		// Hardcoding the number of monkeys. Hopefully this doesn't burn me.
 		let mut checks = [0; 8];
 
-		for _ in 0..20 {
-			for idx in 0..monkeys.len() {
-				checks[idx] += monkeys[idx].items.len();
-				let throws = monkeys[idx].make_throws();
-				for throw in throws {
-					monkeys[throw.dest].items.push(throw.item);
-				}
-			}
+		for _ in 0..self.rounds {
+			play_round(&mut monkeys, &mut checks);
 		}
 
 		// Sort the list of checks.
@@ -215,13 +232,15 @@ impl Advent for Day11 {
 	}
 
 	fn part_two(&self) -> Self::Answer2 {
-		let mut monkeys = self.0.clone();
-		let factor: u32 = self.0.iter().map(|m| m.factor).product();
-		let factor = factor as u64;
+		let mut monkeys = self.monkeys.clone();
+		// Every monkey's test still works modulo the least common multiple
+		// of all of their factors, so we can keep worry levels below that.
+		let factor =
+			Checked(self.monkeys.iter().map(|m| m.factor as u64).fold(1, lcm));
 		// Hardcoding the number of monkeys. Hopefully this doesn't burn me.
 		let mut checks = [0; 8];
 
-		for _ in 0..10_000 {
+		for _ in 0..self.anxious_rounds {
 			for idx in 0..monkeys.len() {
 				checks[idx] += monkeys[idx].items.len();
 				let throws: Vec<_> = {
@@ -230,7 +249,7 @@ impl Advent for Day11 {
 						.iter()
 						.map(|&item| {
 							// Make this number less huge.
-							let item = item as u64;
+							let item = Checked(item as u64);
 
 							let worry = match monkeys[idx].operation {
 								Operation::Add(val) => match val {
@@ -243,7 +262,7 @@ impl Advent for Day11 {
 								},
 							};
 
-							let worry: u32 = (worry % factor) as u32;
+							let worry: u32 = (worry % factor).0 as u32;
 
 							let target = match worry % monkeys[idx].factor {
 								0 => monkeys[idx].decision.0,
@@ -271,15 +290,79 @@ impl Advent for Day11 {
 		// Return the product of the top two results.
 		checks[0] * checks[1]
 	}
+
+	fn set_param(&mut self, name: &str, value: i64) -> Result<(), String> {
+		let value = param_value(name, value)?;
+		match name {
+			"rounds" => self.rounds = value,
+			"anxious_rounds" => self.anxious_rounds = value,
+			_ => return Err(format!("Unknown parameter {}", name)),
+		}
+		Ok(())
+	}
+}
+
+/// Let every monkey take its turn, with the worry relief from part one.
+///
+/// `checks` gets bumped by the number of items each monkey looked at.
+fn play_round(monkeys: &mut [Monkey], checks: &mut [usize]) {
+	for idx in 0..monkeys.len() {
+		checks[idx] += monkeys[idx].items.len();
+		let throws = monkeys[idx].make_throws();
+		for throw in throws {
+			monkeys[throw.dest].items.push(throw.item);
+		}
+	}
+}
+
+impl Repl for Day11 {
+	fn commands() -> Vec<Command<Self>> {
+		vec![Command {
+			name: "round",
+			args: "[count]",
+			help: "Play some rounds (one by default) and show who has what",
+			run: |runner, args| {
+				let rounds = if args.is_empty() {
+					1
+				} else {
+					parse_arg(args, 0, "count")?
+				};
+				let mut checks = vec![0; runner.monkeys.len()];
+				for _ in 0..rounds {
+					play_round(&mut runner.monkeys, &mut checks);
+				}
+				let report = runner
+					.monkeys
+					.iter()
+					.zip(checks)
+					.enumerate()
+					.map(|(idx, (monkey, checks))| {
+						format!(
+							"Monkey {} inspected {} items, now has {:?}",
+							idx, checks, monkey.items
+						)
+					})
+					.collect::<Vec<_>>();
+				Ok(report.join("\n"))
+			},
+		}]
+	}
 }
 
 fn main() {
-	let data = Day11::parse_input(&input_to_str());
-	println!("The peak amount of monkey business is {}", data.part_one());
+	if repl::launch::<Day11>() {
+		return;
+	}
+	let mut runner = Runner::<Day11>::from_stdin();
+	println!(
+		"The peak amount of monkey business is {}",
+		runner.part_one()
+	);
 	println!(
 		"The total amount of monkey business with anxiety is {}",
-		data.part_two()
+		runner.part_two()
 	);
+	runner.report();
 }
 
 #[cfg(test)]
@@ -297,7 +380,7 @@ mod tests {
 			decision: (2, 3),
 		}];
 		let runner = Day11::parse_input(monkey);
-		let actual = runner.0;
+		let actual = runner.monkeys;
 
 		assert_eq!(expected, actual);
 	}
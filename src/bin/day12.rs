@@ -13,7 +13,7 @@
 use std::cmp::{Ordering, Reverse};
 use std::collections::{BinaryHeap, HashSet};
 
-use advent::{input_to_str, Advent};
+use advent::{Advent, Runner};
 
 #[derive(Debug, Clone)]
 struct Day12 {
@@ -278,7 +278,8 @@ impl PartialOrd for PathProgress {
 }
 
 fn main() {
-	let runner = Day12::parse_input(&input_to_str());
+	let mut runner = Runner::<Day12>::from_stdin();
 	println!("Path to the top takes {} steps", runner.part_one());
 	println!("Optimal trail takes {} steps", runner.part_two());
+	runner.report();
 }
@@ -8,7 +8,7 @@
 //! ------
 //! Determine which packets are not in the right order.
 
-use advent::{input_to_str, Advent};
+use advent::{Advent, Runner};
 use std::cmp::Ordering;
 
 #[derive(Clone, Debug)]
@@ -253,9 +253,10 @@ fn parse_packet(data: &str) -> PacketData {
 }
 
 fn main() {
-	let runner = Day13::parse_input(&input_to_str());
+	let mut runner = Runner::<Day13>::from_stdin();
 	println!("The sum of mismatched pairs is {}", runner.part_one());
 	println!("The decoder key for this signal is {}", runner.part_two());
+	runner.report();
 }
 
 fn _copied_parse(
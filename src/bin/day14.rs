@@ -12,7 +12,7 @@
 //! Assuming an infinite floor two spots below the lowest point of the input,
 //! when does the sand clog itself?
 
-use advent::{input_to_str, Advent, Point};
+use advent::{param_value, Advent, Point, Runner};
 use std::collections::HashSet;
 
 const STARTING_POSITION: Point<u32> = Point { x: 500, y: 0 };
@@ -27,6 +27,8 @@ struct Day14 {
 	maze: HashSet<Point<u32>>,
 	/// The point at which sand falls "forever".
 	floor: u32,
+	/// Where the sand pours in from. Defaults to [`STARTING_POSITION`].
+	start: Point<u32>,
 }
 
 impl Advent for Day14 {
@@ -34,6 +36,11 @@ impl Advent for Day14 {
 
 	type Answer2 = u32;
 
+	const PARAMS: &'static [(&'static str, i64)] = &[
+		("start_x", STARTING_POSITION.x as i64),
+		("start_y", STARTING_POSITION.y as i64),
+	];
+
 	fn parse_input(input: &str) -> Self {
 		let mut maze = HashSet::new();
 		// We can reasonably expect that nothing ever goes _higher_
@@ -86,13 +93,17 @@ impl Advent for Day14 {
 				head = *tail;
 			}
 		}
-		Day14 { maze, floor }
+		Day14 {
+			maze,
+			floor,
+			start: STARTING_POSITION,
+		}
 	}
 
 	fn part_one(&self) -> Self::Answer1 {
 		let floor = self.floor;
 		let mut maze = self.maze.clone();
-		let mut sand = STARTING_POSITION;
+		let mut sand = self.start;
 		let mut sand_counter = 0;
 		'fall: loop {
 			// Check to see if we've gone past the floor.
@@ -127,15 +138,25 @@ impl Advent for Day14 {
 
 			maze.insert(sand);
 			sand_counter += 1;
-			sand = STARTING_POSITION;
+			sand = self.start;
 		}
 		sand_counter
 	}
 
+	fn set_param(&mut self, name: &str, value: i64) -> Result<(), String> {
+		let value = param_value(name, value)?;
+		match name {
+			"start_x" => self.start.x = value,
+			"start_y" => self.start.y = value,
+			_ => return Err(format!("Unknown parameter {}", name)),
+		}
+		Ok(())
+	}
+
 	fn part_two(&self) -> Self::Answer2 {
 		let floor = self.floor + 2;
 		let mut maze = self.maze.clone();
-		let mut sand = STARTING_POSITION;
+		let mut sand = self.start;
 		let mut sand_counter = 0;
 		'fall: loop {
 			// Optimization so we don't constantly create points.
@@ -172,11 +193,11 @@ impl Advent for Day14 {
 			// Increment the counter for the answer.
 			sand_counter += 1;
 			// Check to see if we've clogged the cave.
-			if maze.contains(&STARTING_POSITION) {
+			if maze.contains(&self.start) {
 				break;
 			} else {
 				// Otherwise, start again.
-				sand = STARTING_POSITION;
+				sand = self.start;
 			}
 		}
 		sand_counter
@@ -195,7 +216,7 @@ fn parse_point(text: &str) -> Point<u32> {
 }
 
 fn main() {
-	let runner = Day14::parse_input(&input_to_str());
+	let mut runner = Runner::<Day14>::from_stdin();
 	println!(
 		"The total number of sand units before stabilizing is {}",
 		runner.part_one()
@@ -204,6 +225,7 @@ fn main() {
 		"The number of sand units it takes to clog the cave is {}",
 		runner.part_two()
 	);
+	runner.report();
 }
 
 #[cfg(test)]
@@ -10,7 +10,8 @@
 //! ------
 //! What's the _one_ spot that can't have a beacon?
 
-use advent::{input_to_str, Advent, Point};
+use advent::repl::{self, parse_arg, Command, Repl};
+use advent::{Advent, Point, Runner};
 use std::collections::HashSet;
 
 /// The row we care about for the purposes of Part 1's puzzle.
@@ -19,10 +20,20 @@ const MAJOR_ROW: i64 = 2_000_000;
 /// The maximum size of the map for Part 2's puzzle.
 const MAX_COORDINATE: i64 = 4_000_000;
 
+/// What the x coordinate gets multiplied by to get a tuning frequency.
+///
+/// This happens to be the same as [`MAX_COORDINATE`] for the real input,
+/// but it stays put for the example.
+const TUNING_MULTIPLIER: i64 = 4_000_000;
+
 #[derive(Debug)]
 struct Day15 {
 	/// The list of sensors given by the input.
 	sensors: Vec<Sensor>,
+	/// The row Part 1 asks about. Defaults to [`MAJOR_ROW`].
+	major_row: i64,
+	/// The edge of the map for Part 2. Defaults to [`MAX_COORDINATE`].
+	max_coordinate: i64,
 }
 
 impl Advent for Day15 {
@@ -30,6 +41,9 @@ impl Advent for Day15 {
 
 	type Answer2 = u64;
 
+	const PARAMS: &'static [(&'static str, i64)] =
+		&[("major_row", MAJOR_ROW), ("max_coordinate", MAX_COORDINATE)];
+
 	fn parse_input(input: &str) -> Self {
 		let mut sensors = Vec::new();
 		// Format of line: "Sensor at x=A, y=B: closest beacon is at x=C, y=D"
@@ -81,11 +95,15 @@ impl Advent for Day15 {
 				beacon,
 			});
 		}
-		Day15 { sensors }
+		Day15 {
+			sensors,
+			major_row: MAJOR_ROW,
+			max_coordinate: MAX_COORDINATE,
+		}
 	}
 
 	fn part_one(&self) -> Self::Answer1 {
-		self.clear_beacons(MAJOR_ROW)
+		self.clear_beacons(self.major_row)
 	}
 
 	fn part_two(&self) -> u64 {
@@ -93,12 +111,12 @@ impl Advent for Day15 {
 		'sensor: for sensor in self.sensors.iter() {
 			let border_points = sensor.border_coordinates();
 			let border_points = border_points.iter().filter(|&p| {
-				(0 <= p.x && p.x <= MAX_COORDINATE)
-					&& (0 <= p.y && p.y <= MAX_COORDINATE)
+				(0 <= p.x && p.x <= self.max_coordinate)
+					&& (0 <= p.y && p.y <= self.max_coordinate)
 			});
 			for point in border_points {
 				if !self.sensors.iter().any(|s| s.can_sense_point(*point)) {
-					frequency = point.x * MAX_COORDINATE + point.y;
+					frequency = point.x * TUNING_MULTIPLIER + point.y;
 					eprintln!("({}, {}) => {}", point.x, point.y, frequency);
 					break 'sensor;
 				}
@@ -106,6 +124,15 @@ impl Advent for Day15 {
 		}
 		frequency as u64
 	}
+
+	fn set_param(&mut self, name: &str, value: i64) -> Result<(), String> {
+		match name {
+			"major_row" => self.major_row = value,
+			"max_coordinate" => self.max_coordinate = value,
+			_ => return Err(format!("Unknown parameter {}", name)),
+		}
+		Ok(())
+	}
 }
 
 impl Day15 {
@@ -114,7 +141,7 @@ impl Day15 {
 		let mut empty_pos = HashSet::new();
 		for sensor in self.sensors.iter() {
 			let beacon_distance = sensor.beacon_distance();
-			let y_distance = sensor.location.y.abs_diff(MAJOR_ROW);
+			let y_distance = sensor.location.y.abs_diff(row);
 
 			let remainder = beacon_distance.saturating_sub(y_distance);
 			let remainder: i64 =
@@ -213,13 +240,31 @@ impl Sensor {
 	}
 }
 
+impl Repl for Day15 {
+	fn commands() -> Vec<Command<Self>> {
+		vec![Command {
+			name: "row",
+			args: "<y>",
+			help: "Count where beacons can't be in some other row",
+			run: |runner, args| {
+				let row = parse_arg(args, 0, "y")?;
+				Ok(runner.clear_beacons(row).to_string())
+			},
+		}]
+	}
+}
+
 fn main() {
-	let runner = Day15::parse_input(&input_to_str());
+	if repl::launch::<Day15>() {
+		return;
+	}
+	let mut runner = Runner::<Day15>::from_stdin();
 	println!(
 		"The number of squares that can't be beacons is {}",
 		runner.part_one()
 	);
 	println!("The tuning frequency is {}", runner.part_two());
+	runner.report();
 }
 
 #[cfg(test)]
@@ -234,8 +279,20 @@ mod tests {
 		use advent::get_example_input;
 
 		let example = get_example_input(EXAMPLE_FILE);
-		let runner = Day15::parse_input(&example);
+		let mut runner = Day15::parse_input(&example);
+		runner.set_param("major_row", 10).unwrap();
+
+		assert_eq!(runner.part_one(), 26);
+	}
+
+	#[test]
+	fn test_part_two() {
+		use advent::get_example_input;
+
+		let example = get_example_input(EXAMPLE_FILE);
+		let mut runner = Day15::parse_input(&example);
+		runner.set_param("max_coordinate", 20).unwrap();
 
-		assert_eq!(runner.clear_beacons(10), 26);
+		assert_eq!(runner.part_two(), 56_000_011);
 	}
 }
@@ -6,22 +6,34 @@
 //! ------
 //! What's the most pressure we can generate in 30 minutes?
 
-use advent::{input_to_str, Advent};
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use advent::{param_value, Advent, BitSet, Runner};
+use std::collections::{BinaryHeap, HashMap};
 
 /// The total amount of time we have to work with.
 const TOTAL_TIME: u32 = 30;
 
 #[derive(Debug)]
-struct Day16(HashMap<String, Valve>);
+struct Day16 {
+	/// Every valve, in the order they showed up in the input.
+	///
+	/// Valves are referred to by their index in here, so that the search
+	/// can keep track of them with a [`BitSet`] instead of strings.
+	valves: Vec<Valve>,
+	/// The index of the valve we start at.
+	start: usize,
+	/// The total amount of time we have. Defaults to [`TOTAL_TIME`].
+	total_time: u32,
+}
 
 /// Represents the data given from one line of puzzle input.
 #[derive(Clone, Debug, PartialEq, Eq)]
 struct Valve {
+	/// The name of this valve.
+	name: String,
 	/// The flow rate of this valve.
 	flow_rate: u32,
-	/// The other valves this one links to.
-	tunnels: Vec<String>,
+	/// The indices of the other valves this one links to.
+	tunnels: Vec<usize>,
 }
 
 impl Valve {
@@ -31,28 +43,31 @@ impl Valve {
 }
 
 /// A struct representing the state of traversal.
-#[derive(Clone, Debug, PartialEq, Eq)]
+///
+/// This gets copied for every step of the search, so it had better be cheap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 struct Progress {
 	/// The current pressure being released at the moment.
 	pub released_pressure: u32,
 	/// The amount of time passed at this moment..
 	pub time_elapsed: u32,
 	/// The valve currently being visited.
-	pub current_valve: String,
-	/// The names of all of the currently open valves.
-	pub open_valves: HashSet<String>,
+	pub current_valve: usize,
+	/// All of the currently open valves.
+	pub open_valves: BitSet,
 	/// The list of pathways that need not be explored at the current valve.
-	pub visited_valves: HashSet<String>,
+	pub visited_valves: BitSet,
 }
 
-impl Default for Progress {
-	fn default() -> Self {
+impl Progress {
+	/// Start the search at the given valve, with nothing open yet.
+	fn start(valve: usize) -> Self {
 		Progress {
 			released_pressure: 0,
 			time_elapsed: 0,
-			current_valve: String::from("AA"),
-			open_valves: HashSet::new(),
-			visited_valves: HashSet::new(),
+			current_valve: valve,
+			open_valves: BitSet::new(),
+			visited_valves: BitSet::new(),
 		}
 	}
 }
@@ -77,12 +92,16 @@ impl Advent for Day16 {
 
 	type Answer2 = u32;
 
+	const PARAMS: &'static [(&'static str, i64)] =
+		&[("total_time", TOTAL_TIME as i64)];
+
 	fn parse_input(input: &str) -> Self {
-		let mut valves = HashMap::new();
+		let mut valves = Vec::new();
+		let mut tunnel_names = Vec::new();
 		// Format: "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB"
 		for line in input.lines() {
 			let words = line.split(' ').collect::<Vec<_>>();
-			let key = words[1].to_owned();
+			let name = words[1].to_owned();
 			let flow_rate = words[4]
 				.strip_prefix("rate=")
 				.expect("This valve don't got flow!");
@@ -96,28 +115,52 @@ impl Advent for Day16 {
 				.join(" ")
 				.split(", ")
 				.map(|s| s.to_owned())
-				.collect();
+				.collect::<Vec<_>>();
 
-			valves.insert(key, Valve { flow_rate, tunnels });
+			valves.push(Valve {
+				name,
+				flow_rate,
+				tunnels: Vec::new(),
+			});
+			tunnel_names.push(tunnels);
+		}
+		assert!(
+			valves.len() <= BitSet::CAPACITY,
+			"That's too many valves to keep track of!"
+		);
+
+		// Now that every valve has a number, point the tunnels at them.
+		let indices = valves
+			.iter()
+			.enumerate()
+			.map(|(idx, valve)| (valve.name.clone(), idx))
+			.collect::<HashMap<_, _>>();
+		for (valve, tunnels) in valves.iter_mut().zip(tunnel_names) {
+			valve.tunnels = tunnels
+				.iter()
+				.map(|name| *indices.get(name).expect("Tunnel leads nowhere!"))
+				.collect();
 		}
+		let start = *indices.get("AA").expect("Where do we start?");
 
-		Day16(valves)
+		Day16 {
+			valves,
+			start,
+			total_time: TOTAL_TIME,
+		}
 	}
 
 	fn part_one(&self) -> Self::Answer1 {
-		let initial_progress = Progress::default();
+		let initial_progress = Progress::start(self.start);
 		let mut progress_opts = BinaryHeap::new();
 		progress_opts.push(initial_progress);
-		let working_valves = (self.0)
+		let working_valves = self
+			.valves
 			.iter()
-			.filter_map(|(name, valve)| {
-				if valve.flow_rate > 0 {
-					Some(name.clone())
-				} else {
-					None
-				}
-			})
-			.collect::<HashSet<_>>();
+			.enumerate()
+			.filter(|(_, valve)| valve.flow_rate > 0)
+			.map(|(idx, _)| idx)
+			.collect::<BitSet>();
 
 		let mut max_pressure = 0;
 		while let Some(mut progress) = progress_opts.pop() {
@@ -132,7 +175,7 @@ impl Advent for Day16 {
 
 			// Increment the time.
 			let time = progress.time_elapsed + 1;
-			if time >= TOTAL_TIME {
+			if time >= self.total_time {
 				// We've hit our maximum, stop doing work.
 				if progress.released_pressure > max_pressure {
 					max_pressure = progress.released_pressure;
@@ -143,52 +186,57 @@ impl Advent for Day16 {
 			// Adjust time.
 			progress.time_elapsed = time;
 			// Mark this valve as visited.
-			progress
-				.visited_valves
-				.insert(progress.current_valve.clone());
+			progress.visited_valves.insert(progress.current_valve);
 
 			// Get a handle of the valve we care about.
-			let valve = (self.0)
-				.get(&progress.current_valve)
-				.expect("Missing valve here!");
+			let valve = &self.valves[progress.current_valve];
 
 			// Open this valve.
-			if !progress.open_valves.contains(&progress.current_valve)
+			if !progress.open_valves.contains(progress.current_valve)
 				&& valve.flow_rate > 0
 			{
-				let mut next_progress = progress.clone();
-				next_progress
-					.open_valves
-					.insert(progress.current_valve.clone());
-				next_progress.released_pressure += valve
-					.get_pressure_at_time(TOTAL_TIME - progress.time_elapsed);
+				let mut next_progress = progress;
+				next_progress.open_valves.insert(progress.current_valve);
+				next_progress.released_pressure += valve.get_pressure_at_time(
+					self.total_time - progress.time_elapsed,
+				);
 				next_progress.visited_valves.clear();
 				progress_opts.push(next_progress);
 			}
 
 			// Visit other valves.
-			for next_valve in valve
+			for &next_valve in valve
 				.tunnels
 				.iter()
-				.filter(|&v| !progress.visited_valves.contains(v))
+				.filter(|&&v| !progress.visited_valves.contains(v))
 			{
-				let mut next_progress = progress.clone();
-				next_progress.current_valve = next_valve.clone();
+				let mut next_progress = progress;
+				next_progress.current_valve = next_valve;
 				progress_opts.push(next_progress);
 			}
 		}
 		max_pressure
 	}
 
+	fn set_param(&mut self, name: &str, value: i64) -> Result<(), String> {
+		match name {
+			"total_time" => self.total_time = param_value(name, value)?,
+			_ => return Err(format!("Unknown parameter {}", name)),
+		}
+		Ok(())
+	}
+
 	fn part_two(&self) -> Self::Answer2 {
-		// 
+		//
 		todo!()
 	}
 }
 
 fn main() {
-	let runner = Day16::parse_input(&input_to_str());
-	println!("Max pressure in 30 minutes is {}", runner.part_one());
+	let mut runner = Runner::<Day16>::from_stdin();
+	let time = runner.puzzle().total_time;
+	println!("Max pressure in {} minutes is {}", time, runner.part_one());
+	runner.report();
 }
 
 #[cfg(test)]
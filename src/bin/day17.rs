@@ -6,7 +6,7 @@
 //! ------
 //! After 2022 rounds of falling, how high is the tower?
 
-use advent::{input_to_str, Advent};
+use advent::{Advent, Runner};
 
 #[derive(Debug)]
 struct Day17(Vec<WindDirection>);
@@ -42,6 +42,7 @@ impl Advent for Day17 {
 }
 
 fn main() {
-	let runner = Day17::parse_input(&input_to_str());
+	let mut runner = Runner::<Day17>::from_stdin();
 	println!("The top of the stack is {} units high", runner.part_one());
+	runner.report();
 }
@@ -6,7 +6,7 @@
 //! ------
 //! How many cube faces are not met by another cube?
 
-use advent::{input_to_str, Advent};
+use advent::{Advent, Runner};
 
 #[derive(Debug)]
 struct Day18(Vec<Cube>);
@@ -78,42 +78,26 @@ impl Advent for Day18 {
 	fn part_one(&self) -> Self::Answer1 {
 		let mut edges = 0;
 		for cube in self.0.iter() {
+			// A neighbor past the edge of the grid can't exist, so that
+			// face is unmet. No need to wrap around to find that out.
 			let cubes = [
-				Cube {
-					x: cube.x.overflowing_sub(1).0,
-					y: cube.y,
-					z: cube.z,
-				},
-				Cube {
-					x: cube.x.overflowing_add(1).0,
-					y: cube.y,
-					z: cube.z,
-				},
-				Cube {
-					x: cube.x,
-					y: cube.y.overflowing_sub(1).0,
-					z: cube.z,
-				},
-				Cube {
-					x: cube.x,
-					y: cube.y.overflowing_add(1).0,
-					z: cube.z,
-				},
-				Cube {
-					x: cube.x,
-					y: cube.y,
-					z: cube.z.overflowing_sub(1).0,
-				},
-				Cube {
-					x: cube.x,
-					y: cube.y,
-					z: cube.z.overflowing_add(1).0,
-				},
+				cube.x.checked_sub(1).map(|x| Cube { x, ..*cube }),
+				cube.x.checked_add(1).map(|x| Cube { x, ..*cube }),
+				cube.y.checked_sub(1).map(|y| Cube { y, ..*cube }),
+				cube.y.checked_add(1).map(|y| Cube { y, ..*cube }),
+				cube.z.checked_sub(1).map(|z| Cube { z, ..*cube }),
+				cube.z.checked_add(1).map(|z| Cube { z, ..*cube }),
 			];
 			for other_cube in cubes {
-				if !self.0.contains(&other_cube) {
-					eprintln!("{:?} is not adjacent to {:?}", other_cube, cube);
-					edges += 1;
+				match other_cube {
+					Some(other_cube) if self.0.contains(&other_cube) => {}
+					_ => {
+						eprintln!(
+							"{:?} is not adjacent to {:?}",
+							other_cube, cube
+						);
+						edges += 1;
+					}
 				}
 			}
 			// if cube {
@@ -125,8 +109,9 @@ impl Advent for Day18 {
 }
 
 fn main() {
-	let runner = Day18::parse_input(&input_to_str());
+	let mut runner = Runner::<Day18>::from_stdin();
 	println!("There are {} unmet faces.", runner.part_one());
+	runner.report();
 }
 
 #[cfg(test)]
@@ -142,6 +127,13 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_part_one_origin() {
+		// Nothing lives below zero, and that shouldn't wrap around.
+		let runner = Day18::parse_input("0,0,0\n1,0,0");
+		assert_eq!(runner.part_one(), 10);
+	}
+
 	#[test]
 	fn test_part_one() {
 		use advent::get_example_input;
@@ -11,7 +11,7 @@
 //! We are `humn`. What do we need to report so that both of the monkeys
 //! `root` cares about result in the same value?
 
-use advent::{input_to_str, Advent};
+use advent::{Advent, Checked, Runner};
 use std::collections::HashMap;
 
 #[derive(Debug)]
@@ -125,12 +125,7 @@ impl Monkey {
 			Monkey::Operation { op, left, right } => {
 				let left = map.get(left).expect("Missing a monkey!");
 				let right = map.get(right).expect("Missing a monkey!");
-				match op {
-					Operation::Add => left.evaluate(map) + right.evaluate(map),
-					Operation::Sub => left.evaluate(map) - right.evaluate(map),
-					Operation::Mul => left.evaluate(map) * right.evaluate(map),
-					Operation::Div => left.evaluate(map) / right.evaluate(map),
-				}
+				op.apply(left.evaluate(map), right.evaluate(map))
 			}
 			Monkey::Unknown => panic!("Human stupid"),
 		}
@@ -150,12 +145,7 @@ impl Monkey {
 					let one = left.try_evaluate(map);
 					let two = right.try_evaluate(map);
 					match (one, two) {
-						(Some(one), Some(two)) => match op {
-							Operation::Add => Some(one + two),
-							Operation::Sub => Some(one - two),
-							Operation::Mul => Some(one * two),
-							Operation::Div => Some(one / two),
-						},
+						(Some(one), Some(two)) => Some(op.apply(one, two)),
 						(None, Some(_)) => None,
 						(Some(_), None) => None,
 						(None, None) => {
@@ -184,6 +174,7 @@ impl Monkey {
 
 	/// Attempt to resolve this monkey into equaling the target value.
 	fn equal_target(&self, map: &HashMap<String, Monkey>, target: i64) -> i64 {
+		let target = Checked(target);
 		match self {
 			Monkey::Integer(num) => *num,
 			Monkey::Operation { op, left, right } => {
@@ -196,9 +187,9 @@ impl Monkey {
 						// target = constant + unknown
 						if let Some(num) = one {
 							assert!(two.is_none());
-							right.equal_target(map, target - num)
+							right.equal_target(map, (target - num).0)
 						} else if let Some(num) = two {
-							left.equal_target(map, target - num)
+							left.equal_target(map, (target - num).0)
 						} else {
 							panic!("Monkey haystack failure!")
 						}
@@ -208,11 +199,11 @@ impl Monkey {
 							// target = constant - unknown
 							// Ergo, unknown = constant - target
 							assert!(two.is_none());
-							right.equal_target(map, num - target)
+							right.equal_target(map, (Checked(num) - target).0)
 						} else if let Some(num) = two {
 							// target = unknown - constant
 							// Ergo, unknown = target + constant
-							left.equal_target(map, target + num)
+							left.equal_target(map, (target + num).0)
 						} else {
 							panic!("Monkey haystack failure!")
 						}
@@ -222,9 +213,9 @@ impl Monkey {
 						// Ergo, unknown = target / constant
 						if let Some(num) = one {
 							assert!(two.is_none());
-							right.equal_target(map, target / num)
+							right.equal_target(map, (target / num).0)
 						} else if let Some(num) = two {
-							left.equal_target(map, target / num)
+							left.equal_target(map, (target / num).0)
 						} else {
 							panic!("Monkey haystack failure!")
 						}
@@ -234,18 +225,18 @@ impl Monkey {
 							// target = constant / unknown
 							// Ergo, unknown = constant / target
 							assert!(two.is_none());
-							right.equal_target(map, num / target)
+							right.equal_target(map, (Checked(num) / target).0)
 						} else if let Some(num) = two {
 							// target = unknown / constant
 							// Ergo, unknown = target * constant
-							left.equal_target(map, target * num)
+							left.equal_target(map, (target * num).0)
 						} else {
 							panic!("Monkey haystack failure!")
 						}
 					},
 				}
 			}
-			Monkey::Unknown => target,
+			Monkey::Unknown => target.0,
 		}
 	}
 }
@@ -258,10 +249,26 @@ enum Operation {
 	Div,
 }
 
+impl Operation {
+	/// Do the math, panicking instead of wrapping if the monkeys get
+	/// too excited.
+	fn apply(self, left: i64, right: i64) -> i64 {
+		let (left, right) = (Checked(left), Checked(right));
+		let result = match self {
+			Operation::Add => left + right,
+			Operation::Sub => left - right,
+			Operation::Mul => left * right,
+			Operation::Div => left / right,
+		};
+		result.0
+	}
+}
+
 fn main() {
-	let runner = Day21::parse_input(&input_to_str());
+	let mut runner = Runner::<Day21>::from_stdin();
 	println!("Root monkey says {}", runner.part_one());
 	println!("Human monkey needs to say {}", runner.part_two());
+	runner.report();
 }
 
 #[cfg(test)]
@@ -0,0 +1,287 @@
+//! A set of small numbers that fits in a single integer.
+//!
+//! A lot of puzzles boil down to "which of these few dozen things have we
+//! seen?" A [`HashSet`](std::collections::HashSet) works, but cloning one
+//! for every step of a search gets expensive fast. [`BitSet`] is [`Copy`],
+//! hashes as one number, and does unions and intersections in one
+//! instruction.
+
+use std::fmt;
+use std::iter::FusedIterator;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Sub, SubAssign};
+
+/// A set of numbers in the range `0..128`.
+///
+/// Trying to put anything bigger in panics, so map your items to indices
+/// first.
+/// ```
+/// use advent::BitSet;
+///
+/// let evens: BitSet = (0..10).step_by(2).collect();
+/// let small: BitSet = (0..5).collect();
+/// assert_eq!((evens & small).iter().collect::<Vec<_>>(), vec![0, 2, 4]);
+/// assert_eq!((evens | small).len(), 7);
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BitSet(u128);
+
+impl BitSet {
+	/// The number of items this set can hold. Valid items are
+	/// `0..BitSet::CAPACITY`.
+	pub const CAPACITY: usize = u128::BITS as usize;
+
+	/// Make an empty set.
+	pub const fn new() -> Self {
+		BitSet(0)
+	}
+
+	/// Get the bit for an item, complaining if it doesn't fit.
+	fn bit(item: usize) -> u128 {
+		assert!(
+			item < Self::CAPACITY,
+			"{} doesn't fit in a BitSet of {}",
+			item,
+			Self::CAPACITY
+		);
+		1 << item
+	}
+
+	/// Add an item to the set. Returns whether it was new.
+	pub fn insert(&mut self, item: usize) -> bool {
+		let bit = Self::bit(item);
+		let new = self.0 & bit == 0;
+		self.0 |= bit;
+		new
+	}
+
+	/// Take an item out of the set. Returns whether it was there.
+	pub fn remove(&mut self, item: usize) -> bool {
+		let bit = Self::bit(item);
+		let present = self.0 & bit != 0;
+		self.0 &= !bit;
+		present
+	}
+
+	/// Check whether an item is in the set. Anything too big to fit
+	/// is never in the set.
+	pub fn contains(&self, item: usize) -> bool {
+		item < Self::CAPACITY && self.0 & (1 << item) != 0
+	}
+
+	/// The number of items in the set.
+	pub fn len(&self) -> usize {
+		self.0.count_ones() as usize
+	}
+
+	/// Whether there's nothing in the set.
+	pub fn is_empty(&self) -> bool {
+		self.0 == 0
+	}
+
+	/// Empty out the set.
+	pub fn clear(&mut self) {
+		self.0 = 0;
+	}
+
+	/// Everything in either set.
+	pub fn union(self, other: Self) -> Self {
+		BitSet(self.0 | other.0)
+	}
+
+	/// Everything in both sets.
+	pub fn intersection(self, other: Self) -> Self {
+		BitSet(self.0 & other.0)
+	}
+
+	/// Everything in this set that isn't in the other one.
+	pub fn difference(self, other: Self) -> Self {
+		BitSet(self.0 & !other.0)
+	}
+
+	/// Whether everything in this set is also in the other one.
+	pub fn is_subset(self, other: Self) -> bool {
+		self.0 & !other.0 == 0
+	}
+
+	/// The smallest item in the set, if there is one.
+	pub fn first(&self) -> Option<usize> {
+		if self.is_empty() {
+			None
+		} else {
+			Some(self.0.trailing_zeros() as usize)
+		}
+	}
+
+	/// Iterate over the items in the set, smallest first.
+	pub fn iter(&self) -> Iter {
+		Iter(self.0)
+	}
+}
+
+impl fmt::Debug for BitSet {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_set().entries(self.iter()).finish()
+	}
+}
+
+/// An iterator over the items in a [`BitSet`], smallest first.
+#[derive(Clone, Debug)]
+pub struct Iter(u128);
+
+impl Iterator for Iter {
+	type Item = usize;
+
+	fn next(&mut self) -> Option<usize> {
+		if self.0 == 0 {
+			return None;
+		}
+		let item = self.0.trailing_zeros() as usize;
+		// Knock off the lowest set bit.
+		self.0 &= self.0 - 1;
+		Some(item)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let len = self.0.count_ones() as usize;
+		(len, Some(len))
+	}
+}
+
+impl ExactSizeIterator for Iter {}
+
+impl FusedIterator for Iter {}
+
+impl IntoIterator for BitSet {
+	type Item = usize;
+	type IntoIter = Iter;
+
+	fn into_iter(self) -> Iter {
+		self.iter()
+	}
+}
+
+impl IntoIterator for &BitSet {
+	type Item = usize;
+	type IntoIter = Iter;
+
+	fn into_iter(self) -> Iter {
+		self.iter()
+	}
+}
+
+impl FromIterator<usize> for BitSet {
+	fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+		let mut set = BitSet::new();
+		set.extend(iter);
+		set
+	}
+}
+
+impl Extend<usize> for BitSet {
+	fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+		for item in iter {
+			self.insert(item);
+		}
+	}
+}
+
+impl BitOr for BitSet {
+	type Output = Self;
+
+	fn bitor(self, rhs: Self) -> Self {
+		self.union(rhs)
+	}
+}
+
+impl BitOrAssign for BitSet {
+	fn bitor_assign(&mut self, rhs: Self) {
+		*self = self.union(rhs);
+	}
+}
+
+impl BitAnd for BitSet {
+	type Output = Self;
+
+	fn bitand(self, rhs: Self) -> Self {
+		self.intersection(rhs)
+	}
+}
+
+impl BitAndAssign for BitSet {
+	fn bitand_assign(&mut self, rhs: Self) {
+		*self = self.intersection(rhs);
+	}
+}
+
+impl Sub for BitSet {
+	type Output = Self;
+
+	fn sub(self, rhs: Self) -> Self {
+		self.difference(rhs)
+	}
+}
+
+impl SubAssign for BitSet {
+	fn sub_assign(&mut self, rhs: Self) {
+		*self = self.difference(rhs);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_insert_remove() {
+		let mut set = BitSet::new();
+		assert!(set.is_empty());
+		assert!(set.insert(3));
+		assert!(!set.insert(3));
+		assert!(set.insert(127));
+		assert!(set.contains(3) && set.contains(127));
+		assert!(!set.contains(4) && !set.contains(500));
+		assert_eq!(set.len(), 2);
+		assert_eq!(set.first(), Some(3));
+
+		assert!(set.remove(3));
+		assert!(!set.remove(3));
+		assert_eq!(set.iter().collect::<Vec<_>>(), vec![127]);
+		set.clear();
+		assert_eq!(set.first(), None);
+	}
+
+	#[test]
+	fn test_set_operations() {
+		let odds: BitSet = (1..20).step_by(2).collect();
+		let triples: BitSet = (0..20).step_by(3).collect();
+
+		let both = odds & triples;
+		assert_eq!(both.iter().collect::<Vec<_>>(), vec![3, 9, 15]);
+		assert!(both.is_subset(odds) && both.is_subset(triples));
+		assert!(!odds.is_subset(triples));
+		assert_eq!((odds | triples).len(), 10 + 7 - 3);
+		assert_eq!((odds - triples).len(), 10 - 3);
+
+		let mut acc = odds;
+		acc &= triples;
+		acc |= BitSet::from_iter([0]);
+		acc -= BitSet::from_iter([3]);
+		assert_eq!(format!("{:?}", acc), "{0, 9, 15}");
+	}
+
+	#[test]
+	fn test_iter_size() {
+		let set: BitSet = [0, 64, 65, 127].into_iter().collect();
+		let mut iter = set.iter();
+		assert_eq!(iter.len(), 4);
+		iter.next();
+		assert_eq!(iter.len(), 3);
+		assert_eq!(iter.collect::<Vec<_>>(), vec![64, 65, 127]);
+	}
+
+	#[test]
+	#[should_panic(expected = "128 doesn't fit in a BitSet of 128")]
+	fn test_too_big() {
+		BitSet::new().insert(128);
+	}
+}
@@ -0,0 +1,165 @@
+//! Arithmetic that refuses to quietly give the wrong answer.
+//!
+//! In a release build, `u32::MAX + 1` is zero and nobody says a word.
+//! That's a fun way to submit a wrong answer and not know why. Wrap a
+//! number in [`Checked`] and any overflow panics instead, in every build
+//! profile, and the panic says which numbers were involved.
+//! ```
+//! use advent::Checked;
+//!
+//! let worry = Checked(79u32) * 19;
+//! assert_eq!(worry.0, 1501);
+//! ```
+//! ```should_panic
+//! use advent::Checked;
+//!
+//! // Panics with "attempt to compute 4294967295 + 1 overflowed u32".
+//! let _ = Checked(u32::MAX) + 1;
+//! ```
+
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{
+	Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub,
+	SubAssign,
+};
+
+/// An integer that panics on overflow instead of wrapping around.
+///
+/// Division by zero panics too, which is what the plain integers do anyway,
+/// but at least now the message says what was being divided.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Checked<T>(pub T);
+
+impl<T: fmt::Display> fmt::Display for Checked<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.0.fmt(f)
+	}
+}
+
+/// Implement one operator (and its assigning friend) for [`Checked`],
+/// both against another [`Checked`] and against a bare number.
+macro_rules! checked_op {
+	($t:ty, $op:ident, $func:ident, $assign:ident, $assign_func:ident,
+	$checked:ident, $symbol:literal) => {
+		impl $op for Checked<$t> {
+			type Output = Self;
+
+			#[inline]
+			fn $func(self, rhs: Self) -> Self {
+				match self.0.$checked(rhs.0) {
+					Some(val) => Checked(val),
+					// Only division and remainder can fail on zero.
+					None if rhs.0 == 0 => panic!(
+						concat!("attempt to compute {} ", $symbol, " 0"),
+						self.0
+					),
+					None => panic!(
+						concat!(
+							"attempt to compute {} ",
+							$symbol,
+							" {} overflowed {}"
+						),
+						self.0,
+						rhs.0,
+						stringify!($t)
+					),
+				}
+			}
+		}
+
+		impl $op<$t> for Checked<$t> {
+			type Output = Self;
+
+			#[inline]
+			fn $func(self, rhs: $t) -> Self {
+				self.$func(Checked(rhs))
+			}
+		}
+
+		impl $assign for Checked<$t> {
+			#[inline]
+			fn $assign_func(&mut self, rhs: Self) {
+				*self = self.$func(rhs);
+			}
+		}
+
+		impl $assign<$t> for Checked<$t> {
+			#[inline]
+			fn $assign_func(&mut self, rhs: $t) {
+				*self = self.$func(Checked(rhs));
+			}
+		}
+	};
+}
+
+macro_rules! checked_impl {
+	($($t:ty)*) => {$(
+		checked_op!($t, Add, add, AddAssign, add_assign, checked_add, "+");
+		checked_op!($t, Sub, sub, SubAssign, sub_assign, checked_sub, "-");
+		checked_op!($t, Mul, mul, MulAssign, mul_assign, checked_mul, "*");
+		checked_op!($t, Div, div, DivAssign, div_assign, checked_div, "/");
+		checked_op!($t, Rem, rem, RemAssign, rem_assign, checked_rem, "%");
+
+		impl Sum for Checked<$t> {
+			fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+				iter.fold(Checked(0), |acc, n| acc + n)
+			}
+		}
+
+		impl Product for Checked<$t> {
+			fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+				iter.fold(Checked(1), |acc, n| acc * n)
+			}
+		}
+	)*};
+}
+
+checked_impl! { u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_in_range() {
+		assert_eq!(Checked(2u32) + 3, Checked(5));
+		assert_eq!(Checked(2i64) - 3, Checked(-1));
+		assert_eq!(Checked(7u64) % Checked(4), Checked(3));
+
+		let mut worry = Checked(10u32);
+		worry *= 3;
+		worry /= 4;
+		assert_eq!(worry, Checked(7));
+
+		let product: Checked<u64> =
+			[2, 3, 5].map(Checked).into_iter().product();
+		assert_eq!(product, Checked(30));
+	}
+
+	#[test]
+	#[should_panic(expected = "attempt to compute 0 - 1 overflowed u32")]
+	fn test_underflow() {
+		let _ = Checked(0u32) - 1;
+	}
+
+	#[test]
+	#[should_panic(expected = "attempt to compute -128 / -1 overflowed i8")]
+	fn test_divide_overflow() {
+		let _ = Checked(i8::MIN) / -1;
+	}
+
+	#[test]
+	#[should_panic(
+		expected = "attempt to compute 65536 * 65536 overflowed u32"
+	)]
+	fn test_overflow() {
+		let _ = Checked(65_536u32) * Checked(65_536);
+	}
+
+	#[test]
+	#[should_panic(expected = "attempt to compute 1 / 0")]
+	fn test_divide_by_zero() {
+		let _ = Checked(1i64) / 0;
+	}
+}
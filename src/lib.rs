@@ -5,7 +5,20 @@ use std::io::Read;
 use std::ops::{Add, AddAssign, Sub};
 
 mod advent;
-pub use crate::advent::Advent;
+pub mod bitset;
+mod checked;
+pub mod math;
+pub mod memory;
+pub mod repl;
+pub mod rng;
+mod runner;
+pub mod scaffold;
+pub mod streaming;
+pub mod vfs;
+pub use crate::advent::{param_value, set_param, Advent};
+pub use crate::bitset::BitSet;
+pub use crate::checked::Checked;
+pub use crate::runner::Runner;
 
 #[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 /// A point struct since I keep switching up x and y coordinates.
@@ -108,8 +121,8 @@ pub fn point<T>(x: T, y: T) -> Point<T> {
 pub fn input_to_str() -> String {
 	stdin()
 		.lines()
-		.filter_map(|l| match l {
-			Ok(l) => Some(l),
+		.map(|l| match l {
+			Ok(l) => l,
 			Err(err) => panic!("Welp, your input failed: {}", err),
 		})
 		.collect::<Vec<_>>()
@@ -1,64 +1,91 @@
-//! Day 1's Advent of Code puzzle
-//! =============================
-//! Should this be in `main.rs`? No, but the file was already there and I didn't
-//! feel like moving it. Maybe later.
+//! The runner
+//! ==========
+//! Day 1 used to live here, back when it was the only day. It's moved out to
+//! `src/bin/day01.rs` now, and the default binary just gets you to the rest.
 //!
-//! Puzzle input consists of a list of numbers, occasionally separated by
-//! empty lines. These represent a series of elves with varying amounts
-//! of snacks. An empty line separates one elf's collection from another.
-//! Non-empty lines represent the calorie count of the current snack.
-//! These elves are hungry.
-//!
-//! Part 1
-//! ------
-//! Return the elf with the best snacks. (Read: the elf with the highest
-//! calorie count)
-//!
-//! Part 2
-//! ------
-//! Return the calorie count of the top THREE elves, using the same criteria
-//! as part 1.
+//! - `cargo run -- new 19` writes out `src/bin/day19.rs` along with an empty
+//!   example input to paste into.
+//! - `cargo run -- 7 [args...]` runs day 7 with whatever's on stdin, passing
+//!   the rest of the arguments along, so `--stats`, `--param` and `repl` all
+//!   work as usual. The days are separate binaries, so this needs them built
+//!   first (`cargo build --bins`).
+
+use std::env;
+use std::path::Path;
+use std::process::{self, Command};
+
+use advent::scaffold;
+
+const USAGE: &str = "Usage: cargo run -- new <day>\n       \
+	cargo run -- <day> [args...]";
 
-use std::io::stdin;
 fn main() {
-	let mut elves = vec![0];
-	let mut index = 0;
-	let lines = stdin().lines();
-	for line in lines {
-		if let Ok(line) = line {
-			if line.is_empty() {
-				// If the line is empty, we move onto the next elf.
-				elves.push(0);
-				index += 1;
-			} else {
-				// Otherwise, we add the current value to the current elf.
-				if let Ok(cal) = line.parse::<i32>() {
-					elves[index] += cal;
-				} else {
-					// Realistically, this should never be reached.
-					// After all, the input is pre-prepared and we should be
-					// able to trust it. The infosec people are currently
-					// laughing at my naivete.
-					panic!("Invalid value")
-				}
+	let args = env::args().skip(1).collect::<Vec<_>>();
+	match args.first().map(String::as_str) {
+		Some("new") => new_day(args.get(1).map(String::as_str)),
+		Some(day) => run_day(day, &args[1..]),
+		None => {
+			eprintln!("{}", USAGE);
+			process::exit(2);
+		}
+	}
+}
+
+/// Parse a day off the command line, or give up with the usage.
+fn parse_day(day: Option<&str>) -> u32 {
+	match day.map(str::parse) {
+		Some(Ok(day)) => day,
+		_ => {
+			eprintln!("{}", USAGE);
+			process::exit(2);
+		}
+	}
+}
+
+/// Handle the `new <day>` subcommand.
+fn new_day(day: Option<&str>) {
+	let day = parse_day(day);
+	let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+	match scaffold::new_day(root, day) {
+		Ok(created) => {
+			for path in created {
+				println!("Created {}", path.display());
 			}
-		} else {
-			// We shouldn't really get here, because this implies something
-			// went wrong that's WAAAAAAY out of our league.
-			panic!("AAAAAAAAAAH!")
+		}
+		Err(err) => {
+			eprintln!("Couldn't scaffold day {}: {}", day, err);
+			process::exit(1);
 		}
 	}
-	let elf = Iterator::max(elves.iter());
-	if let Some(elf) = elf {
-		println!("The correct elf is {}", elf);
-	} else {
-		// This shouldn't be reached, because otherwise it means
-		// that the input is empty and I've just wasted this computer's time.
-		panic!("AAAAAAAAAAAAH")
+}
+
+/// Hand off to a day's binary, which sits next to this one.
+fn run_day(day: &str, args: &[String]) {
+	let day = parse_day(Some(day));
+	let name = format!("day{:02}{}", day, env::consts::EXE_SUFFIX);
+	let binary = match env::current_exe() {
+		Ok(exe) => exe.with_file_name(name),
+		Err(err) => {
+			eprintln!("Couldn't find where I live: {}", err);
+			process::exit(1);
+		}
+	};
+	if !binary.exists() {
+		eprintln!(
+			"There's no {}. Does day {} exist, and have you run \
+			`cargo build --bins`?",
+			binary.display(),
+			day
+		);
+		process::exit(1);
+	}
+	// stdin, stdout and stderr are all inherited, so the day can't tell
+	// the difference.
+	match Command::new(&binary).args(args).status() {
+		Ok(status) => process::exit(status.code().unwrap_or(1)),
+		Err(err) => {
+			eprintln!("Couldn't run {}: {}", binary.display(), err);
+			process::exit(1);
+		}
 	}
-	// This gets the three elves for part 2. Relatively simple.
-	let mut sorts = elves.clone();
-	sorts.sort_by(|a, b| b.cmp(a));
-	let sum = sorts[0] + sorts[1] + sorts[2];
-	println!("The sum of the top three elves is {}", sum);
 }
@@ -0,0 +1,233 @@
+//! Number theory, for when the puzzle turns into modular arithmetic.
+//!
+//! Advent of Code loves a good "these numbers are all prime, wink wink"
+//! puzzle. These helpers do the math properly, so the solution still works
+//! when they aren't.
+
+use crate::Checked;
+
+/// The greatest common divisor of two numbers. `gcd(0, 0)` is zero.
+/// ```
+/// use advent::math::gcd;
+///
+/// assert_eq!(gcd(12, 18), 6);
+/// assert_eq!(gcd(17, 5), 1);
+/// ```
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+	while b != 0 {
+		(a, b) = (b, a % b);
+	}
+	a
+}
+
+/// The least common multiple of two numbers. If either is zero, so is this.
+///
+/// Panics if the result doesn't fit in a [`u64`].
+/// ```
+/// use advent::math::lcm;
+///
+/// assert_eq!(lcm(4, 6), 12);
+/// assert_eq!([23, 19, 13, 17].into_iter().fold(1, lcm), 96_577);
+/// ```
+pub fn lcm(a: u64, b: u64) -> u64 {
+	if a == 0 || b == 0 {
+		return 0;
+	}
+	(Checked(a / gcd(a, b)) * b).0
+}
+
+/// The extended Euclidean algorithm.
+///
+/// Returns `(g, x, y)` such that `a * x + b * y == g`, where `g` is the
+/// (non-negative) greatest common divisor of `a` and `b`.
+/// ```
+/// use advent::math::extended_gcd;
+///
+/// let (g, x, y) = extended_gcd(240, 46);
+/// assert_eq!(g, 2);
+/// assert_eq!(240 * x + 46 * y, 2);
+/// ```
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+	let (mut old_r, mut r) = (a, b);
+	let (mut old_s, mut s) = (1, 0);
+	let (mut old_t, mut t) = (0, 1);
+	while r != 0 {
+		let quotient = old_r / r;
+		(old_r, r) = (r, old_r - quotient * r);
+		(old_s, s) = (s, old_s - quotient * s);
+		(old_t, t) = (t, old_t - quotient * t);
+	}
+	if old_r < 0 {
+		(-old_r, -old_s, -old_t)
+	} else {
+		(old_r, old_s, old_t)
+	}
+}
+
+/// The inverse of `a` modulo `modulus`, in the range `0..modulus`.
+///
+/// Returns [`None`] if there isn't one, which happens when `a` and
+/// `modulus` share a factor, or if `modulus` isn't positive.
+/// ```
+/// use advent::math::mod_inverse;
+///
+/// assert_eq!(mod_inverse(3, 11), Some(4));
+/// assert_eq!(mod_inverse(6, 9), None);
+/// ```
+pub fn mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+	if modulus <= 0 {
+		return None;
+	}
+	let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+	if g == 1 {
+		Some(x.rem_euclid(modulus))
+	} else {
+		None
+	}
+}
+
+/// Compute `base.pow(exp) % modulus` without the intermediate value
+/// getting enormous.
+/// ```
+/// use advent::math::mod_pow;
+///
+/// assert_eq!(mod_pow(4, 13, 497), 445);
+/// assert_eq!(mod_pow(2, 0, 1), 0);
+/// ```
+pub fn mod_pow(base: u64, mut exp: u64, modulus: u64) -> u64 {
+	assert_ne!(modulus, 0, "Can't take a remainder from nothing");
+	// Multiplying two numbers less than a u64 always fits in a u128.
+	let modulus = modulus as u128;
+	let mut base = base as u128 % modulus;
+	let mut result = 1 % modulus;
+	while exp > 0 {
+		if exp & 1 == 1 {
+			result = result * base % modulus;
+		}
+		base = base * base % modulus;
+		exp >>= 1;
+	}
+	result as u64
+}
+
+/// The Chinese remainder theorem.
+///
+/// Takes a list of `(residue, modulus)` pairs and finds the smallest
+/// non-negative `x` such that `x % modulus == residue` for every pair.
+/// Returns `(x, m)` where `m` is the least common multiple of the moduli,
+/// so every `x + k * m` works too.
+///
+/// The moduli don't need to be coprime. If the congruences contradict each
+/// other, a modulus isn't positive, or the combined modulus is too big for
+/// an `i64`, this returns [`None`]. An empty list is satisfied by
+/// everything, so that's `Some((0, 1))`.
+/// ```
+/// use advent::math::crt;
+///
+/// assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+/// assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+/// assert_eq!(crt(&[(0, 4), (1, 6)]), None);
+/// assert_eq!(crt(&[(0, i64::MAX), (0, 2)]), None);
+/// ```
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+	// Work in i128 so that merging two congruences can't overflow
+	// before we've had a chance to reduce it.
+	let mut result: i128 = 0;
+	let mut modulus: i128 = 1;
+	for &(residue, next) in congruences {
+		if next <= 0 {
+			return None;
+		}
+		let (residue, next) = (residue as i128, next as i128);
+		// We need result + modulus * k = residue (mod next).
+		let (g, inverse, _) =
+			extended_gcd((modulus % next) as i64, next as i64);
+		let (g, inverse) = (g as i128, inverse as i128);
+		let diff = residue - result;
+		if diff % g != 0 {
+			return None;
+		}
+		let step = next / g;
+		let k = (diff / g % step * inverse).rem_euclid(step);
+		result += modulus * k;
+		modulus *= step;
+		result = result.rem_euclid(modulus);
+		if modulus > i64::MAX as i128 {
+			return None;
+		}
+	}
+	Some((result as i64, modulus as i64))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_extended_gcd_signs() {
+		for (a, b) in [(0, 0), (0, 7), (7, 0), (-12, 18), (12, -18), (-5, -3)] {
+			let (g, x, y) = extended_gcd(a, b);
+			assert!(g >= 0);
+			assert_eq!(a * x + b * y, g, "({}, {})", a, b);
+			assert_eq!(g as u64, gcd(a.unsigned_abs(), b.unsigned_abs()));
+		}
+	}
+
+	#[test]
+	fn test_mod_inverse() {
+		for modulus in 2..30 {
+			for a in -30..30i64 {
+				match mod_inverse(a, modulus) {
+					Some(inv) => {
+						assert!((0..modulus).contains(&inv));
+						assert_eq!((a * inv).rem_euclid(modulus), 1);
+					}
+					None => assert_ne!(
+						gcd(a.unsigned_abs(), modulus as u64),
+						1,
+						"{} mod {} should have an inverse",
+						a,
+						modulus
+					),
+				}
+			}
+		}
+		assert_eq!(mod_inverse(3, 0), None);
+	}
+
+	#[test]
+	fn test_crt_brute_force() {
+		// Check every combination of small congruences against just
+		// counting up until one works.
+		let moduli = [2, 3, 4, 6, 9];
+		for &m1 in &moduli {
+			for &m2 in &moduli {
+				for r1 in 0..m1 {
+					for r2 in 0..m2 {
+						let expected = (0..m1 * m2)
+							.find(|x| x % m1 == r1 && x % m2 == r2)
+							.map(|x| (x, lcm(m1 as u64, m2 as u64) as i64));
+						assert_eq!(crt(&[(r1, m1), (r2, m2)]), expected);
+					}
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_crt_large() {
+		// Big enough that multiplying naively in i64 would overflow.
+		let big = 1_000_000_007;
+		let other = 998_244_353;
+		let (x, m) = crt(&[(5, big), (7, other)]).unwrap();
+		assert_eq!(m, big * other);
+		assert_eq!(x % big, 5);
+		assert_eq!(x % other, 7);
+	}
+
+	#[test]
+	fn test_crt_negative_residue() {
+		assert_eq!(crt(&[(-1, 5)]), Some((4, 5)));
+		assert_eq!(crt(&[]), Some((0, 1)));
+	}
+}
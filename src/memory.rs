@@ -0,0 +1,132 @@
+//! Allocation tracking, because some of these solutions clone like there's
+//! no tomorrow and I'd like to know _where_ the memory goes.
+//!
+//! The library installs [`CountingAllocator`] as the global allocator, so
+//! every binary that links against it gets the counters for free. Use
+//! [`measure`] to find out what a chunk of code cost.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// The number of allocations (and reallocations) made so far.
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+/// The total number of bytes ever handed out.
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+/// The number of bytes currently in use.
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+/// The highest value [`LIVE`] has reached since the last reset.
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// A thin wrapper around [`System`] that keeps count of what it hands out.
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+	/// Record that `size` more bytes are now live.
+	fn grow(size: usize) {
+		let live = LIVE.fetch_add(size, Ordering::Relaxed) + size;
+		PEAK.fetch_max(live, Ordering::Relaxed);
+	}
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		let ptr = System.alloc(layout);
+		if !ptr.is_null() {
+			ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+			ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+			Self::grow(layout.size());
+		}
+		ptr
+	}
+
+	unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+		let ptr = System.alloc_zeroed(layout);
+		if !ptr.is_null() {
+			ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+			ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+			Self::grow(layout.size());
+		}
+		ptr
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		System.dealloc(ptr, layout);
+		LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+	}
+
+	/// A reallocation counts as a brand new allocation of `new_size` bytes,
+	/// since that's what it would cost if it couldn't happen in place.
+	unsafe fn realloc(
+		&self,
+		ptr: *mut u8,
+		layout: Layout,
+		new_size: usize,
+	) -> *mut u8 {
+		let new_ptr = System.realloc(ptr, layout, new_size);
+		if !new_ptr.is_null() {
+			ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+			ALLOCATED.fetch_add(new_size, Ordering::Relaxed);
+			LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+			Self::grow(new_size);
+		}
+		new_ptr
+	}
+}
+
+/// What a chunk of code cost in terms of memory.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Usage {
+	/// How many times the allocator was called.
+	pub allocations: usize,
+	/// How many bytes were handed out in total, freed or not.
+	pub bytes: usize,
+	/// The most bytes that were in use at any one time.
+	///
+	/// Note that this includes whatever was already live beforehand,
+	/// such as the puzzle input.
+	pub peak: usize,
+}
+
+impl fmt::Display for Usage {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{:>12} {:>14} {:>14}",
+			self.allocations, self.bytes, self.peak
+		)
+	}
+}
+
+/// Run `func` and report how much memory it chewed through.
+///
+/// The counters are global, so anything happening on other threads at
+/// the same time gets blamed on `func` too.
+/// ```
+/// use advent::memory::measure;
+///
+/// let (list, usage) = measure(|| vec![0u8; 1024]);
+/// assert_eq!(list.len(), 1024);
+/// assert!(usage.allocations >= 1);
+/// assert!(usage.bytes >= 1024);
+/// ```
+pub fn measure<F, R>(func: F) -> (R, Usage)
+where
+	F: FnOnce() -> R,
+{
+	let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+	let bytes = ALLOCATED.load(Ordering::Relaxed);
+	PEAK.store(LIVE.load(Ordering::Relaxed), Ordering::Relaxed);
+
+	let result = func();
+
+	let usage = Usage {
+		allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+		bytes: ALLOCATED.load(Ordering::Relaxed) - bytes,
+		peak: PEAK.load(Ordering::Relaxed),
+	};
+	(result, usage)
+}
@@ -0,0 +1,250 @@
+//! A little shell for poking at a parsed puzzle.
+//!
+//! Adding `eprintln!` and recompiling gets old. Start a day with
+//! `repl <input file>` instead, like
+//! `cargo run --bin day15 -- repl src/input/day15.txt`, and the input is
+//! parsed once and then you get a prompt. Every day gets the built-in
+//! commands (try `help`), and days can register their own through [`Repl`].
+
+use std::any::type_name;
+use std::env;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io::{stdin, stdout, Write};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::memory::measure;
+use crate::{set_param, Advent};
+
+/// A command a day can add to its REPL.
+pub struct Command<T> {
+	/// What to type to run this command.
+	pub name: &'static str,
+	/// The arguments this command takes, like `<row>`.
+	pub args: &'static str,
+	/// A line about what this command does.
+	pub help: &'static str,
+	/// Do the thing. Gets the puzzle and whatever was typed after the
+	/// command's name, and returns what should be printed.
+	pub run: fn(&mut T, &[&str]) -> Result<String, String>,
+}
+
+/// A puzzle that can be explored from the REPL.
+///
+/// The default gets you the built-in commands and nothing else, so an empty
+/// `impl Repl for DayNN {}` is a fine start.
+pub trait Repl: Advent + fmt::Debug + Sized {
+	/// The commands this day adds on top of the built-in ones.
+	fn commands() -> Vec<Command<Self>> {
+		Vec::new()
+	}
+}
+
+/// Parse a single argument for a command, with a useful complaint if that
+/// doesn't work.
+/// ```
+/// use advent::repl::parse_arg;
+///
+/// assert_eq!(parse_arg::<i64>(&["10"], 0, "row"), Ok(10));
+/// assert!(parse_arg::<i64>(&["ten"], 0, "row").is_err());
+/// assert!(parse_arg::<i64>(&[], 0, "row").is_err());
+/// ```
+pub fn parse_arg<F>(args: &[&str], idx: usize, name: &str) -> Result<F, String>
+where
+	F: std::str::FromStr,
+	F::Err: Display,
+{
+	let arg = args.get(idx).ok_or_else(|| format!("Missing <{}>", name))?;
+	arg.parse()
+		.map_err(|err| format!("Bad <{}> {:?}: {}", name, arg, err))
+}
+
+/// Start the REPL if the binary was run with `repl <input file>`.
+///
+/// Returns whether it did, so `main` knows to stop there:
+/// ```no_run
+/// # use advent::{Advent, Runner};
+/// # use advent::repl::{self, Repl};
+/// # #[derive(Debug)]
+/// # struct Day00;
+/// # impl Advent for Day00 {
+/// #     type Answer1 = u32;
+/// #     type Answer2 = u32;
+/// #     fn parse_input(_: &str) -> Self { Day00 }
+/// #     fn part_one(&self) -> u32 { 0 }
+/// # }
+/// # impl Repl for Day00 {}
+/// if repl::launch::<Day00>() {
+///     return;
+/// }
+/// let mut runner = Runner::<Day00>::from_stdin();
+/// ```
+pub fn launch<T>() -> bool
+where
+	T: Repl,
+	T::Answer1: Display,
+	T::Answer2: Display,
+{
+	let args = env::args().skip(1).collect::<Vec<_>>();
+	if args.first().map(String::as_str) != Some("repl") {
+		return false;
+	}
+	match args.get(1) {
+		Some(path) => run::<T>(path),
+		None => eprintln!("Usage: repl <input file>"),
+	}
+	true
+}
+
+/// What happened after a command ran.
+enum Outcome {
+	Continue,
+	Quit,
+}
+
+/// Run the REPL on the puzzle input in the file at `path`.
+pub fn run<T>(path: &str)
+where
+	T: Repl,
+	T::Answer1: Display,
+	T::Answer2: Display,
+{
+	let load = || -> Result<T, String> {
+		let input = fs::read_to_string(path)
+			.map_err(|err| format!("Couldn't read {}: {}", path, err))?;
+		// Strip the line ending off the end, like `input_to_str` would.
+		let input = input.trim_end_matches(['\r', '\n']);
+		catch_unwind(|| T::parse_input(input))
+			.map_err(|_| format!("Couldn't parse {}", path))
+	};
+	let mut puzzle = match load() {
+		Ok(puzzle) => puzzle,
+		Err(err) => {
+			eprintln!("{}", err);
+			return;
+		}
+	};
+	let commands = T::commands();
+	let name = type_name::<T>().rsplit("::").next().unwrap_or("advent");
+	let prompt = format!("{}> ", name.to_lowercase());
+	let mut stats = false;
+	println!("Parsed {}. Type `help` for a list of commands.", path);
+
+	let mut lines = stdin().lines();
+	loop {
+		print!("{}", prompt);
+		// If stdout is gone, there's nobody to talk to anyway.
+		if stdout().flush().is_err() {
+			break;
+		}
+		let line = match lines.next() {
+			Some(Ok(line)) => line,
+			Some(Err(err)) => {
+				eprintln!("Welp, your input failed: {}", err);
+				break;
+			}
+			// End of input, so finish the prompt's line and leave.
+			None => {
+				println!();
+				break;
+			}
+		};
+		let words = line.split_whitespace().collect::<Vec<_>>();
+		let Some((&word, args)) = words.split_first() else {
+			continue;
+		};
+
+		// Commands run with the puzzle at their mercy, and a panic
+		// shouldn't take the whole session down with it.
+		let (outcome, usage) = measure(|| {
+			catch_unwind(AssertUnwindSafe(|| match word {
+				"help" => {
+					print_help(&commands);
+					Ok(Outcome::Continue)
+				}
+				"one" => {
+					println!("{}", puzzle.part_one());
+					Ok(Outcome::Continue)
+				}
+				"two" => {
+					println!("{}", puzzle.part_two());
+					Ok(Outcome::Continue)
+				}
+				"debug" => {
+					println!("{:#?}", puzzle);
+					Ok(Outcome::Continue)
+				}
+				"reload" => {
+					puzzle = load()?;
+					println!("Reloaded {}", path);
+					Ok(Outcome::Continue)
+				}
+				"params" => {
+					for (name, default) in T::PARAMS {
+						println!("  {:<20} default {}", name, default);
+					}
+					Ok(Outcome::Continue)
+				}
+				"set" => {
+					let name = parse_arg::<String>(args, 0, "name")?;
+					let value = parse_arg(args, 1, "value")?;
+					set_param(&mut puzzle, &name, value)?;
+					Ok(Outcome::Continue)
+				}
+				"stats" => {
+					stats = !stats;
+					println!("Stats are {}", if stats { "on" } else { "off" });
+					Ok(Outcome::Continue)
+				}
+				"quit" | "exit" => Ok(Outcome::Quit),
+				_ => match commands.iter().find(|c| c.name == word) {
+					Some(command) => {
+						println!("{}", (command.run)(&mut puzzle, args)?);
+						Ok(Outcome::Continue)
+					}
+					None => {
+						Err(format!("Unknown command {:?}, try `help`", word))
+					}
+				},
+			}))
+		});
+		match outcome {
+			Ok(Ok(Outcome::Quit)) => break,
+			Ok(Ok(Outcome::Continue)) => {}
+			Ok(Err(err)) => eprintln!("{}", err),
+			// The panic hook has already said what went wrong.
+			Err(_) => eprintln!("That command panicked."),
+		}
+		if stats {
+			eprintln!(
+				"{:>12} {:>14} {:>14}",
+				"allocations", "bytes", "peak bytes"
+			);
+			eprintln!("{}", usage);
+		}
+	}
+}
+
+/// List every command this REPL knows about.
+fn print_help<T>(commands: &[Command<T>]) {
+	let builtins = [
+		("one", "", "Solve part 1"),
+		("two", "", "Solve part 2"),
+		("debug", "", "Print the parsed puzzle"),
+		(
+			"reload",
+			"",
+			"Parse the input file again, with default params",
+		),
+		("params", "", "List the parameters and their defaults"),
+		("set", "<name> <value>", "Change a parameter"),
+		("stats", "", "Toggle allocation stats after each command"),
+		("help", "", "Print this list"),
+		("quit", "", "Leave"),
+	];
+	let extra = commands.iter().map(|c| (c.name, c.args, c.help));
+	for (name, args, help) in builtins.into_iter().chain(extra) {
+		let usage = format!("{} {}", name, args);
+		println!("  {:<20} {}", usage.trim_end(), help);
+	}
+}
@@ -0,0 +1,33 @@
+//! A tiny seeded random number generator, for tests that want a pile of
+//! inputs without me typing them all out.
+//!
+//! It's the classic C `rand` recipe. Terrible for anything that matters,
+//! perfectly fine for making the same little mess every time.
+
+/// A linear congruential generator. The same seed gives the same numbers.
+pub struct Rng(u32);
+
+impl Rng {
+	/// Starts a generator from a seed.
+	pub fn new(seed: u32) -> Self {
+		Rng(seed)
+	}
+
+	/// The next number, somewhere in `0..max`.
+	///
+	/// Panics if `max` is zero, since there's nothing to pick from.
+	/// ```
+	/// use advent::rng::Rng;
+	///
+	/// let mut rng = Rng::new(7);
+	/// let rolls = (0..100).map(|_| rng.below(6)).collect::<Vec<_>>();
+	/// assert!(rolls.iter().all(|&roll| roll < 6));
+	///
+	/// let mut again = Rng::new(7);
+	/// assert_eq!(rolls[0], again.below(6));
+	/// ```
+	pub fn below(&mut self, max: u32) -> u32 {
+		self.0 = self.0.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+		(self.0 >> 16) % max
+	}
+}
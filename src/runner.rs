@@ -0,0 +1,214 @@
+use std::env;
+use std::fmt;
+use std::io::stdin;
+use std::process;
+
+use crate::memory::{measure, Usage};
+use crate::streaming::{parse_reader_from, set_state_param, StreamingAdvent};
+use crate::{input_to_str, set_param, Advent};
+
+/// Runs a puzzle from stdin, keeping track of what each step cost.
+///
+/// Pass `--stats` to the binary to get a table of allocations for the
+/// parse and each part printed to stderr once [`Runner::report`] is called.
+///
+/// Puzzles with [`Advent::PARAMS`] can have them overridden by passing
+/// `--param name=value`, as many times as needed. Streaming puzzles get
+/// theirs before any input is read, through
+/// [`StreamingAdvent::set_state_param`].
+///
+/// Each day picks how its input gets read by picking a constructor:
+/// [`Runner::from_stdin`] reads everything up front, while
+/// [`Runner::from_stdin_streaming`] hands it over one record at a time.
+/// ```no_run
+/// use advent::{Advent, Runner};
+///
+/// struct Day00(String);
+///
+/// impl Advent for Day00 {
+///     type Answer1 = usize;
+///     type Answer2 = usize;
+///
+///     fn parse_input(input: &str) -> Self {
+///         Day00(input.to_owned())
+///     }
+///
+///     fn part_one(&self) -> usize {
+///         self.0.len()
+///     }
+/// }
+///
+/// let mut runner = Runner::<Day00>::from_stdin();
+/// println!("The input is {} bytes long", runner.part_one());
+/// runner.report();
+/// ```
+pub struct Runner<T> {
+	/// The parsed puzzle.
+	puzzle: T,
+	/// What each step has cost so far, in the order they were run.
+	usage: Vec<(&'static str, Usage)>,
+	/// Whether we were asked to print the usage table.
+	stats: bool,
+}
+
+impl<T: Advent> Runner<T> {
+	/// Read all of stdin and parse it into the puzzle.
+	pub fn from_stdin() -> Self {
+		let options = Options::from_args();
+		let input = input_to_str();
+		let (puzzle, usage) = measure(|| T::parse_input(&input));
+		Runner::new(puzzle, usage, options)
+	}
+
+	/// Read all of stdin and parse it with something that might not like
+	/// it. If it doesn't, the error gets printed and we exit, instead of
+	/// panicking like [`Advent::parse_input`] has to.
+	pub fn try_from_stdin<E: fmt::Display>(
+		parse: fn(&str) -> Result<T, E>,
+	) -> Self {
+		let options = Options::from_args();
+		let input = input_to_str();
+		match measure(|| parse(&input)) {
+			(Ok(puzzle), usage) => Runner::new(puzzle, usage, options),
+			(Err(err), _) => {
+				eprintln!("{}", err);
+				process::exit(1);
+			}
+		}
+	}
+
+	/// Solve part one, recording what it cost.
+	pub fn part_one(&mut self) -> T::Answer1 {
+		let (answer, usage) = measure(|| self.puzzle.part_one());
+		self.usage.push(("part one", usage));
+		answer
+	}
+
+	/// Solve part two, recording what it cost.
+	pub fn part_two(&mut self) -> T::Answer2 {
+		let (answer, usage) = measure(|| self.puzzle.part_two());
+		self.usage.push(("part two", usage));
+		answer
+	}
+
+	/// Get a handle on the parsed puzzle.
+	pub fn puzzle(&self) -> &T {
+		&self.puzzle
+	}
+
+	/// Wrap up a freshly parsed puzzle, and set whatever parameters we were
+	/// asked to.
+	fn new(mut puzzle: T, parse: Usage, options: Options) -> Self {
+		options.apply(|name, value| set_param(&mut puzzle, name, value));
+		Runner {
+			puzzle,
+			usage: vec![("parse", parse)],
+			stats: options.stats,
+		}
+	}
+}
+
+/// What the command line asked for.
+struct Options {
+	/// Whether to print the usage table.
+	stats: bool,
+	/// Every `--param`, in the order they were given.
+	params: Vec<(String, i64)>,
+}
+
+impl Options {
+	/// Check the arguments for what we should be doing. This happens before
+	/// any input is read, so streaming puzzles can get their parameters
+	/// first.
+	///
+	/// Bad arguments aren't worth panicking over, so this complains and
+	/// exits instead.
+	fn from_args() -> Self {
+		let mut options = Options {
+			stats: false,
+			params: Vec::new(),
+		};
+		let mut args = env::args().skip(1);
+		while let Some(arg) = args.next() {
+			match arg.as_str() {
+				"--stats" => options.stats = true,
+				"--param" => {
+					let param = args.next().unwrap_or_default();
+					match parse_param(&param) {
+						Ok(param) => options.params.push(param),
+						Err(err) => {
+							eprintln!("Bad --param {:?}: {}", param, err);
+							process::exit(2);
+						}
+					}
+				}
+				_ => {
+					eprintln!("Unknown argument {:?}", arg);
+					process::exit(2);
+				}
+			}
+		}
+		options
+	}
+
+	/// Hand every parameter to `set`, and give up if one doesn't take.
+	fn apply(&self, mut set: impl FnMut(&str, i64) -> Result<(), String>) {
+		for (name, value) in &self.params {
+			if let Err(err) = set(name, *value) {
+				let param = format!("{}={}", name, value);
+				eprintln!("Bad --param {:?}: {}", param, err);
+				process::exit(2);
+			}
+		}
+	}
+}
+
+/// Split up a parameter written as `name=value`.
+fn parse_param(param: &str) -> Result<(String, i64), String> {
+	let (name, value) = param
+		.split_once('=')
+		.ok_or_else(|| String::from("Expected name=value"))?;
+	let value = value
+		.parse()
+		.map_err(|err| format!("{:?} isn't a number: {}", value, err))?;
+	Ok((name.to_string(), value))
+}
+
+impl<T: StreamingAdvent> Runner<T> {
+	/// Parse the puzzle from stdin one record at a time, so the whole input
+	/// never has to fit in memory.
+	pub fn from_stdin_streaming() -> Self {
+		let options = Options::from_args();
+		// Parameters might change how the input gets read, so they go in
+		// before any of it does.
+		let mut state = T::State::default();
+		options
+			.apply(|name, value| set_state_param::<T>(&mut state, name, value));
+		let (puzzle, usage) = measure(|| {
+			parse_reader_from(state, stdin().lock()).unwrap_or_else(|err| {
+				panic!("Welp, your input failed: {}", err)
+			})
+		});
+		Runner {
+			puzzle,
+			usage: vec![("parse", usage)],
+			stats: options.stats,
+		}
+	}
+}
+
+impl<T> Runner<T> {
+	/// Print the usage table to stderr, if `--stats` was passed.
+	pub fn report(&self) {
+		if !self.stats {
+			return;
+		}
+		eprintln!(
+			"{:<10} {:>12} {:>14} {:>14}",
+			"step", "allocations", "bytes", "peak bytes"
+		);
+		for (step, usage) in &self.usage {
+			eprintln!("{:<10} {}", step, usage);
+		}
+	}
+}
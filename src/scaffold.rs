@@ -0,0 +1,94 @@
+//! Scaffolding for a new day's puzzle.
+//!
+//! Every day starts out the same way: a doc header, a `DayNN` struct,
+//! an [`Advent`](crate::Advent) impl, a `main` that hands it to the
+//! [`Runner`](crate::Runner), and a test against the example input.
+//! Rather than copy-paste all of that again, [`new_day`] writes it out.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// The skeleton of a day's binary. See [`render`] for what gets filled in.
+const TEMPLATE: &str = include_str!("scaffold/day.rs.template");
+
+/// Fill in the template for the given day.
+///
+/// The template has three holes:
+/// - `{day}` is the day as written in prose, like `9`.
+/// - `{padded}` is the day as written in file names, like `09`.
+/// - `{rule}` is the underline for the doc header.
+pub fn render(day: u32) -> String {
+	let title = format!("Day {}'s Advent of Code puzzle", day);
+	TEMPLATE
+		.replace("{day}", &day.to_string())
+		.replace("{padded}", &format!("{:02}", day))
+		.replace("{rule}", &"=".repeat(title.len()))
+}
+
+/// Create the binary and an empty example input for a new day.
+///
+/// Files are created relative to `root`, which should be the crate root.
+/// Nothing gets overwritten: if the day already exists, this bails with
+/// [`io::ErrorKind::AlreadyExists`]. Returns the paths that were created.
+pub fn new_day(root: &Path, day: u32) -> io::Result<Vec<PathBuf>> {
+	if !(1..=25).contains(&day) {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			format!("Day {} isn't on the advent calendar", day),
+		));
+	}
+
+	let source = root.join(format!("src/bin/day{:02}.rs", day));
+	let example = root.join(format!("src/input/day{:02}-example.txt", day));
+	if source.exists() {
+		return Err(io::Error::new(
+			io::ErrorKind::AlreadyExists,
+			format!("{} already exists", source.display()),
+		));
+	}
+
+	// The input directory isn't checked in, so it may not be there yet.
+	fs::create_dir_all(root.join("src/input"))?;
+	OpenOptions::new()
+		.write(true)
+		.create_new(true)
+		.open(&source)?
+		.write_all(render(day).as_bytes())?;
+	let mut created = vec![source];
+	// Don't clobber an example someone already pasted in.
+	if !example.exists() {
+		fs::File::create(&example)?;
+		created.push(example);
+	}
+	Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_render() {
+		let source = render(9);
+
+		assert!(source.starts_with(
+			"//! Day 9's Advent of Code puzzle\n\
+			//! =============================\n"
+		));
+		assert!(source.contains("struct Day09(Vec<String>);"));
+		assert!(source.contains("Runner::<Day09>::from_stdin()"));
+		assert!(source.contains("\"src/input/day09-example.txt\""));
+		assert!(!source.contains("{padded}"));
+	}
+
+	#[test]
+	fn test_new_day_bad_day() {
+		let root = Path::new("/nonexistent");
+
+		let err = new_day(root, 26).unwrap_err();
+		assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+		let err = new_day(root, 0).unwrap_err();
+		assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+	}
+}
@@ -0,0 +1,162 @@
+//! Input that's too big to hold in memory all at once.
+//!
+//! [`input_to_str`](crate::input_to_str) reads _everything_ before the
+//! puzzle gets a look at it. That's fine for a few kilobytes of elf
+//! inventory, less fine for a few gigabytes of generated signal. Days that
+//! implement [`StreamingAdvent`] instead get their input one record at a
+//! time, and only keep whatever they need.
+
+use std::io::{self, BufRead};
+use std::ops::ControlFlow;
+
+use crate::advent::check_param;
+use crate::Advent;
+
+/// How a streaming puzzle wants its input cut up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Records {
+	/// One line at a time, without the line ending.
+	Lines,
+	/// One byte at a time. Yes, really. Line endings included.
+	Bytes,
+}
+
+/// A companion to [`Advent`] for puzzles that can be parsed on the fly.
+///
+/// Instead of getting the whole input in [`Advent::parse_input`], the
+/// puzzle keeps a [`State`](StreamingAdvent::State) around, gets handed
+/// each record in turn, and turns the state into itself at the end. Once
+/// that's done, it's solved like any other puzzle.
+///
+/// By the time the puzzle exists the input is long gone, so parameters go
+/// to the state instead, before anything's been read. See
+/// [`StreamingAdvent::set_state_param`].
+pub trait StreamingAdvent: Advent + Sized {
+	/// Whatever needs to be remembered between records.
+	type State: Default;
+
+	/// How the input should be split into records.
+	const RECORDS: Records = Records::Lines;
+
+	/// Look at the next record. Return [`ControlFlow::Break`] if there's no
+	/// point in reading any further.
+	fn consume(state: &mut Self::State, record: &[u8]) -> ControlFlow<()>;
+
+	/// All of the input has been read (or we bailed early).
+	fn finish(state: Self::State) -> Self;
+
+	/// Change one of the parameters listed in [`Advent::PARAMS`], before any
+	/// input has been read. This is what the runner calls for streaming
+	/// puzzles, instead of [`Advent::set_param`].
+	///
+	/// Like [`Advent::set_param`], this only gets called with a name from
+	/// the list, but the value could be anything.
+	fn set_state_param(
+		_state: &mut Self::State,
+		name: &str,
+		value: i64,
+	) -> Result<(), String> {
+		Err(format!("Can't set {} to {}", name, value))
+	}
+}
+
+/// Set a parameter on a streaming puzzle's state by name, checking that the
+/// puzzle actually has it. The streaming version of
+/// [`set_param`](crate::set_param).
+pub fn set_state_param<T: StreamingAdvent>(
+	state: &mut T::State,
+	name: &str,
+	value: i64,
+) -> Result<(), String> {
+	check_param::<T>(name)?;
+	T::set_state_param(state, name, value)
+}
+
+/// Feed everything in `reader` through a streaming puzzle.
+///
+/// Since `&[u8]` is a [`BufRead`], this doubles as a way to implement
+/// [`Advent::parse_input`] for a streaming puzzle:
+/// ```
+/// use advent::streaming::{parse_reader, StreamingAdvent};
+/// use advent::Advent;
+/// use std::ops::ControlFlow;
+///
+/// /// Counts the lines of input, and nothing else.
+/// struct Lines(usize);
+///
+/// impl Advent for Lines {
+///     type Answer1 = usize;
+///     type Answer2 = usize;
+///
+///     fn parse_input(input: &str) -> Self {
+///         parse_reader(input.as_bytes()).expect("Reading from memory")
+///     }
+///
+///     fn part_one(&self) -> usize {
+///         self.0
+///     }
+/// }
+///
+/// impl StreamingAdvent for Lines {
+///     type State = usize;
+///
+///     fn consume(count: &mut usize, _: &[u8]) -> ControlFlow<()> {
+///         *count += 1;
+///         ControlFlow::Continue(())
+///     }
+///
+///     fn finish(count: usize) -> Self {
+///         Lines(count)
+///     }
+/// }
+///
+/// assert_eq!(Lines::parse_input("a\nb\r\nc").part_one(), 3);
+/// ```
+pub fn parse_reader<T, R>(reader: R) -> io::Result<T>
+where
+	T: StreamingAdvent,
+	R: BufRead,
+{
+	parse_reader_from(T::State::default(), reader)
+}
+
+/// Feed everything in `reader` through a streaming puzzle, starting from a
+/// state that's already been set up, like with [`set_state_param`].
+pub fn parse_reader_from<T, R>(
+	mut state: T::State,
+	mut reader: R,
+) -> io::Result<T>
+where
+	T: StreamingAdvent,
+	R: BufRead,
+{
+	match T::RECORDS {
+		Records::Lines => {
+			let mut line = Vec::new();
+			while reader.read_until(b'\n', &mut line)? > 0 {
+				let mut record = line.as_slice();
+				if let Some(rest) = record.strip_suffix(b"\n") {
+					record = rest.strip_suffix(b"\r").unwrap_or(rest);
+				}
+				if T::consume(&mut state, record).is_break() {
+					break;
+				}
+				line.clear();
+			}
+		}
+		Records::Bytes => 'read: loop {
+			let buffer = reader.fill_buf()?;
+			if buffer.is_empty() {
+				break;
+			}
+			for byte in buffer.chunks(1) {
+				if T::consume(&mut state, byte).is_break() {
+					break 'read;
+				}
+			}
+			let length = buffer.len();
+			reader.consume(length);
+		},
+	}
+	Ok(T::finish(state))
+}
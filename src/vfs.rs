@@ -0,0 +1,635 @@
+//! A filesystem pieced back together from somebody poking around a terminal.
+//!
+//! Day 7 hands over a transcript of `cd` and `ls` and asks questions about
+//! the disk it was run on. [`VirtualFs`] replays one of those and keeps what
+//! it learns, so the questions don't have to care about the transcript.
+//!
+//! Everything lives in one big [`Vec`] and refers to everything else by
+//! index, which beats a pile of `Rc<RefCell<_>>` pointing at each other.
+//!
+//! To check the transcript was understood, [`VirtualFs::tree`] draws it like
+//! the puzzle does, and [`VirtualFs::du`] pretends to be `du -h`.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+
+/// Where to find something in a [`VirtualFs`]. Only good for the filesystem
+/// that handed it out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+/// What's at a [`NodeId`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Kind {
+	File,
+	/// Children are kept by name, so everything comes out in order.
+	Directory(BTreeMap<String, NodeId>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Node {
+	name: String,
+	parent: Option<NodeId>,
+	kind: Kind,
+	/// Files know their size from the listing. Directories have theirs
+	/// added up once the transcript is done.
+	size: usize,
+}
+
+/// Everything that can go wrong replaying a transcript. Lines are counted
+/// from one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FsError {
+	/// A command other than `cd` or `ls`.
+	UnknownCommand { line: usize, command: String },
+	/// `cd` somewhere that no `ls` has shown us.
+	UnknownDirectory { line: usize, name: String },
+	/// `cd` into a file.
+	NotADirectory { line: usize, name: String },
+	/// `cd ..` while already at `/`.
+	AboveRoot { line: usize },
+	/// A line of `ls` output that isn't `dir <name>` or `<size> <name>`.
+	BadListing { line: usize, text: String },
+	/// Output that no `ls` asked for.
+	StrayOutput { line: usize, text: String },
+	/// The same name listed as two different things.
+	Conflict { line: usize, name: String },
+}
+
+impl fmt::Display for FsError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			FsError::UnknownCommand { line, command } => {
+				write!(f, "Line {}: {:?} is not a valid command", line, command)
+			}
+			FsError::UnknownDirectory { line, name } => write!(
+				f,
+				"Line {}: looking for {:?}, has anyone seen it?",
+				line, name
+			),
+			FsError::NotADirectory { line, name } => {
+				write!(
+					f,
+					"Line {}: {:?} is a file, you can't cd into it",
+					line, name
+				)
+			}
+			FsError::AboveRoot { line } => {
+				write!(f, "Line {}: / doesn't have a parent", line)
+			}
+			FsError::BadListing { line, text } => {
+				write!(f, "Line {}: can't make sense of {:?}", line, text)
+			}
+			FsError::StrayOutput { line, text } => {
+				write!(f, "Line {}: {:?} isn't the output of an ls", line, text)
+			}
+			FsError::Conflict { line, name } => write!(
+				f,
+				"Line {}: {:?} was listed as something else before",
+				line, name
+			),
+		}
+	}
+}
+
+impl std::error::Error for FsError {}
+
+/// A filesystem rebuilt from a terminal transcript.
+///
+/// Directories only exist once an `ls` has shown them. Sizes are added up
+/// once, at the end of [`VirtualFs::replay`], so asking for them is free.
+/// ```
+/// use advent::vfs::VirtualFs;
+///
+/// let transcript = "$ cd /\n$ ls\ndir a\n10 b.txt\n$ cd a\n$ ls\n5 c";
+/// let fs = VirtualFs::replay(transcript).unwrap();
+/// let a = fs.lookup("/a").unwrap();
+/// assert_eq!(fs.size(a), 5);
+/// assert_eq!(fs.size(fs.root()), 15);
+/// assert_eq!(fs.parent(a), Some(fs.root()));
+/// let paths = fs.directories().map(|(path, _)| path).collect::<Vec<_>>();
+/// assert_eq!(paths, vec!["/", "/a"]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VirtualFs {
+	/// The root is always first, and children always come after their
+	/// parents.
+	nodes: Vec<Node>,
+}
+
+impl VirtualFs {
+	/// Follow along with a transcript, remembering everything `ls` shows.
+	pub fn replay(transcript: &str) -> Result<Self, FsError> {
+		let mut fs = VirtualFs {
+			nodes: vec![Node {
+				name: String::new(),
+				parent: None,
+				kind: Kind::Directory(BTreeMap::new()),
+				size: 0,
+			}],
+		};
+		let mut cwd = fs.root();
+		let mut listing = false;
+		for (index, text) in transcript.lines().enumerate() {
+			let line = index + 1;
+			if let Some(command) = text.strip_prefix("$ ") {
+				listing = false;
+				match command.split_once(' ') {
+					Some(("cd", name)) => cwd = fs.cd(cwd, name, line)?,
+					None if command == "ls" => listing = true,
+					_ => {
+						return Err(FsError::UnknownCommand {
+							line,
+							command: command.to_string(),
+						})
+					}
+				}
+			} else if text.is_empty() {
+				// Somebody hit enter a few extra times. No harm done.
+				continue;
+			} else if !listing {
+				return Err(FsError::StrayOutput {
+					line,
+					text: text.to_string(),
+				});
+			} else {
+				fs.list(cwd, text, line)?;
+			}
+		}
+		fs.add_up();
+		Ok(fs)
+	}
+
+	/// Work out where a `cd` takes us.
+	fn cd(
+		&self,
+		cwd: NodeId,
+		name: &str,
+		line: usize,
+	) -> Result<NodeId, FsError> {
+		match name {
+			"/" => Ok(self.root()),
+			".." => self.parent(cwd).ok_or(FsError::AboveRoot { line }),
+			_ => match self.child(cwd, name) {
+				Some(id) if self.is_dir(id) => Ok(id),
+				Some(_) => Err(FsError::NotADirectory {
+					line,
+					name: name.to_string(),
+				}),
+				None => Err(FsError::UnknownDirectory {
+					line,
+					name: name.to_string(),
+				}),
+			},
+		}
+	}
+
+	/// Take note of one line of `ls` output. Listing the same thing twice is
+	/// fine, as long as the story doesn't change.
+	fn list(
+		&mut self,
+		cwd: NodeId,
+		text: &str,
+		line: usize,
+	) -> Result<(), FsError> {
+		let bad = || FsError::BadListing {
+			line,
+			text: text.to_string(),
+		};
+		let (size_or_dir, name) = text.split_once(' ').ok_or_else(bad)?;
+		let size = match size_or_dir {
+			"dir" => None,
+			size => Some(size.parse::<usize>().map_err(|_| bad())?),
+		};
+		if name.is_empty() || name.contains('/') || name == "." || name == ".."
+		{
+			return Err(bad());
+		}
+		if let Some(existing) = self.child(cwd, name) {
+			let node = &self.nodes[existing.0];
+			return match (&node.kind, size) {
+				(Kind::Directory(_), None) => Ok(()),
+				(Kind::File, Some(size)) if node.size == size => Ok(()),
+				_ => Err(FsError::Conflict {
+					line,
+					name: name.to_string(),
+				}),
+			};
+		}
+		let id = NodeId(self.nodes.len());
+		self.nodes.push(Node {
+			name: name.to_string(),
+			parent: Some(cwd),
+			kind: match size {
+				None => Kind::Directory(BTreeMap::new()),
+				Some(_) => Kind::File,
+			},
+			size: size.unwrap_or(0),
+		});
+		if let Kind::Directory(children) = &mut self.nodes[cwd.0].kind {
+			children.insert(name.to_string(), id);
+		}
+		Ok(())
+	}
+
+	/// Work out every directory's size. Children always come after their
+	/// parents, so going backwards means each child is finished before it
+	/// gets added to its parent.
+	fn add_up(&mut self) {
+		for index in (1..self.nodes.len()).rev() {
+			let size = self.nodes[index].size;
+			if let Some(parent) = self.nodes[index].parent {
+				self.nodes[parent.0].size += size;
+			}
+		}
+	}
+
+	/// `/`, where it all starts.
+	pub fn root(&self) -> NodeId {
+		NodeId(0)
+	}
+
+	/// Find whatever lives at an absolute path like `/a/e/i`.
+	pub fn lookup(&self, path: &str) -> Option<NodeId> {
+		path.strip_prefix('/')?
+			.split('/')
+			.filter(|name| !name.is_empty())
+			.try_fold(self.root(), |id, name| self.child(id, name))
+	}
+
+	/// Find something directly inside a directory.
+	pub fn child(&self, id: NodeId, name: &str) -> Option<NodeId> {
+		match &self.nodes[id.0].kind {
+			Kind::Directory(children) => children.get(name).copied(),
+			Kind::File => None,
+		}
+	}
+
+	/// Everything directly inside a directory, in order by name. Files don't
+	/// have anything inside them.
+	pub fn children(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+		let children = match &self.nodes[id.0].kind {
+			Kind::Directory(children) => Some(children.values().copied()),
+			Kind::File => None,
+		};
+		children.into_iter().flatten()
+	}
+
+	/// The directory something lives in, unless it's the root.
+	pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+		self.nodes[id.0].parent
+	}
+
+	/// The name something was listed under. The root doesn't have one.
+	pub fn name(&self, id: NodeId) -> &str {
+		&self.nodes[id.0].name
+	}
+
+	/// Whether something is a directory. Everything else is a file.
+	pub fn is_dir(&self, id: NodeId) -> bool {
+		matches!(self.nodes[id.0].kind, Kind::Directory(_))
+	}
+
+	/// How big something is. For directories, that's everything inside,
+	/// as far as we know.
+	pub fn size(&self, id: NodeId) -> usize {
+		self.nodes[id.0].size
+	}
+
+	/// The full path to something, like `/a/e`.
+	pub fn path(&self, id: NodeId) -> String {
+		let mut names = Vec::new();
+		let mut current = Some(id);
+		while let Some(id) = current.filter(|&id| id != self.root()) {
+			names.push(self.name(id));
+			current = self.parent(id);
+		}
+		names.reverse();
+		format!("/{}", names.join("/"))
+	}
+
+	/// How many directories down something is. The root is at the top, at
+	/// depth zero.
+	pub fn depth(&self, id: NodeId) -> usize {
+		std::iter::successors(self.parent(id), |&id| self.parent(id)).count()
+	}
+
+	/// Draw the whole thing the way the puzzle does, except directories get
+	/// their sizes too.
+	/// ```text
+	/// - / (dir, size=15)
+	///   - a (dir, size=5)
+	///     - c (file, size=5)
+	///   - b.txt (file, size=10)
+	/// ```
+	pub fn tree(&self) -> String {
+		let mut out = String::new();
+		self.draw(self.root(), 0, &mut out);
+		out
+	}
+
+	/// Draw something and everything under it, `depth` levels in.
+	fn draw(&self, id: NodeId, depth: usize, out: &mut String) {
+		let name = if id == self.root() {
+			"/"
+		} else {
+			self.name(id)
+		};
+		let kind = if self.is_dir(id) { "dir" } else { "file" };
+		// Writing to a String can't fail.
+		let _ = writeln!(
+			out,
+			"{:indent$}- {} ({}, size={})",
+			"",
+			name,
+			kind,
+			self.size(id),
+			indent = depth * 2
+		);
+		for child in self.children(id) {
+			self.draw(child, depth + 1, out);
+		}
+	}
+
+	/// Directory sizes like `du -h --max-depth=N | sort -hr` would give
+	/// them, biggest first. Without a limit, every directory shows up.
+	/// ```text
+	/// 15      /
+	/// 5       /a
+	/// ```
+	pub fn du(&self, max_depth: Option<usize>) -> String {
+		let mut rows = self
+			.directories()
+			.filter(|&(_, id)| {
+				max_depth.is_none_or(|max| self.depth(id) <= max)
+			})
+			.map(|(path, id)| (self.size(id), path))
+			.collect::<Vec<_>>();
+		// Biggest first, and alphabetical when that doesn't settle it.
+		rows.sort_by(|(a, a_path), (b, b_path)| {
+			b.cmp(a).then(a_path.cmp(b_path))
+		});
+		rows.iter()
+			.map(|(size, path)| format!("{:<8}{}\n", human_size(*size), path))
+			.collect()
+	}
+
+	/// Every directory along with its full path, parents first and siblings
+	/// in order by name.
+	pub fn directories(&self) -> impl Iterator<Item = (String, NodeId)> + '_ {
+		let mut stack = vec![self.root()];
+		std::iter::from_fn(move || {
+			let id = stack.pop()?;
+			let subdirectories = self.children(id).filter(|&c| self.is_dir(c));
+			// Backwards, so the first one comes off the stack first.
+			stack.extend(subdirectories.collect::<Vec<_>>().into_iter().rev());
+			Some((self.path(id), id))
+		})
+	}
+}
+
+/// Write a size the way `du -h` does, in powers of 1024 and always rounding
+/// up, so nothing looks smaller than it is. Small numbers get one decimal
+/// place.
+/// ```
+/// use advent::vfs::human_size;
+///
+/// assert_eq!(human_size(584), "584");
+/// assert_eq!(human_size(1024), "1.0K");
+/// assert_eq!(human_size(94_853), "93K");
+/// assert_eq!(human_size(48_381_165), "47M");
+/// ```
+pub fn human_size(bytes: usize) -> String {
+	const UNITS: [&str; 6] = ["K", "M", "G", "T", "P", "E"];
+	if bytes < 1024 {
+		return bytes.to_string();
+	}
+	// Find the biggest unit that still leaves at least one of them. Work in
+	// u128 so that the exabytes don't overflow anything.
+	let bytes = bytes as u128;
+	let mut scale = 1024u128;
+	let mut unit = 0;
+	while bytes >= scale * 1024 && unit + 1 < UNITS.len() {
+		scale *= 1024;
+		unit += 1;
+	}
+	// Tenths of a unit, rounded up.
+	let tenths = (bytes * 10).div_ceil(scale);
+	if tenths < 100 {
+		format!("{}.{}{}", tenths / 10, tenths % 10, UNITS[unit])
+	} else {
+		format!("{}{}", bytes.div_ceil(scale), UNITS[unit])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k";
+
+	#[test]
+	fn test_example_sizes() {
+		let fs = VirtualFs::replay(EXAMPLE).unwrap();
+		let sizes = fs
+			.directories()
+			.map(|(path, id)| (path, fs.size(id)))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			sizes,
+			vec![
+				("/".to_string(), 48381165),
+				("/a".to_string(), 94853),
+				("/a/e".to_string(), 584),
+				("/d".to_string(), 24933642),
+			]
+		);
+	}
+
+	#[test]
+	fn test_navigation() {
+		let fs = VirtualFs::replay(EXAMPLE).unwrap();
+		let i = fs.lookup("/a/e/i").unwrap();
+		assert!(!fs.is_dir(i));
+		assert_eq!(fs.size(i), 584);
+		assert_eq!(fs.name(i), "i");
+		assert_eq!(fs.path(i), "/a/e/i");
+		let e = fs.parent(i).unwrap();
+		assert_eq!(Some(e), fs.lookup("/a/e/"));
+		assert_eq!(fs.parent(fs.parent(e).unwrap()), Some(fs.root()));
+		assert_eq!(fs.parent(fs.root()), None);
+		assert_eq!(fs.lookup("/"), Some(fs.root()));
+		assert_eq!(fs.path(fs.root()), "/");
+		assert_eq!(fs.lookup("/a/nope"), None);
+		assert_eq!(fs.lookup("/b.txt/x"), None);
+		assert_eq!(fs.lookup("a"), None);
+		let names = fs
+			.children(fs.root())
+			.map(|id| fs.name(id))
+			.collect::<Vec<_>>();
+		assert_eq!(names, vec!["a", "b.txt", "c.dat", "d"]);
+		assert_eq!(fs.children(i).count(), 0);
+	}
+
+	#[test]
+	fn test_tree() {
+		let fs = VirtualFs::replay(EXAMPLE).unwrap();
+		let expected = "\
+- / (dir, size=48381165)
+  - a (dir, size=94853)
+    - e (dir, size=584)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir, size=24933642)
+    - d.ext (file, size=5626152)
+    - d.log (file, size=8033020)
+    - j (file, size=4060174)
+    - k (file, size=7214296)
+";
+		assert_eq!(fs.tree(), expected);
+		let empty = VirtualFs::replay("").unwrap();
+		assert_eq!(empty.tree(), "- / (dir, size=0)\n");
+	}
+
+	#[test]
+	fn test_du() {
+		let fs = VirtualFs::replay(EXAMPLE).unwrap();
+		assert_eq!(fs.du(Some(0)), "47M     /\n");
+		assert_eq!(fs.du(Some(1)), "47M     /\n24M     /d\n93K     /a\n");
+		let everything = "47M     /\n24M     /d\n93K     /a\n584     /a/e\n";
+		assert_eq!(fs.du(None), everything);
+		assert_eq!(fs.du(Some(5)), everything);
+		assert_eq!(fs.depth(fs.lookup("/a/e/i").unwrap()), 3);
+	}
+
+	#[test]
+	fn test_human_size() {
+		let cases = [
+			(0, "0"),
+			(1023, "1023"),
+			(1025, "1.1K"),
+			(10 * 1024 - 1, "10K"),
+			(10 * 1024, "10K"),
+			(1023 * 1024, "1023K"),
+			(1024 * 1024, "1.0M"),
+			(1024 * 1024 * 1024 + 1, "1.1G"),
+			(usize::MAX, "16E"),
+		];
+		for (bytes, expected) in cases {
+			assert_eq!(human_size(bytes), expected, "{}", bytes);
+		}
+	}
+
+	#[test]
+	fn test_relisting() {
+		// Looking twice doesn't count anything twice.
+		let transcript = "$ ls\n5 a\ndir b\n$ cd b\n$ cd ..\n$ ls\n5 a\ndir b";
+		let fs = VirtualFs::replay(transcript).unwrap();
+		assert_eq!(fs.size(fs.root()), 5);
+		assert_eq!(fs.children(fs.root()).count(), 2);
+	}
+
+	#[test]
+	fn test_errors() {
+		let cases = [
+			(
+				"$ rm -rf /",
+				FsError::UnknownCommand {
+					line: 1,
+					command: "rm -rf /".to_string(),
+				},
+			),
+			(
+				"$ ls\ndir a\n$ cd b",
+				FsError::UnknownDirectory {
+					line: 3,
+					name: "b".to_string(),
+				},
+			),
+			(
+				"$ ls\n1 a\n$ cd a",
+				FsError::NotADirectory {
+					line: 3,
+					name: "a".to_string(),
+				},
+			),
+			("$ cd ..", FsError::AboveRoot { line: 1 }),
+			(
+				"$ ls\nbig a",
+				FsError::BadListing {
+					line: 2,
+					text: "big a".to_string(),
+				},
+			),
+			(
+				"$ ls\ndir ..",
+				FsError::BadListing {
+					line: 2,
+					text: "dir ..".to_string(),
+				},
+			),
+			(
+				"1 a",
+				FsError::StrayOutput {
+					line: 1,
+					text: "1 a".to_string(),
+				},
+			),
+			(
+				"$ ls\n1 a\n$ cd /\n1 a",
+				FsError::StrayOutput {
+					line: 4,
+					text: "1 a".to_string(),
+				},
+			),
+			(
+				"$ ls\n1 a\n$ ls\ndir a",
+				FsError::Conflict {
+					line: 4,
+					name: "a".to_string(),
+				},
+			),
+			(
+				"$ ls\n1 a\n$ ls\n2 a",
+				FsError::Conflict {
+					line: 4,
+					name: "a".to_string(),
+				},
+			),
+		];
+		for (transcript, error) in cases {
+			assert_eq!(
+				VirtualFs::replay(transcript),
+				Err(error),
+				"{}",
+				transcript
+			);
+		}
+	}
+}